@@ -0,0 +1,109 @@
+//! Compares the original sequential scan of the ledger, fetching one window of blocks at a time
+//! and checking the ownership of records one by one, with the scan fetching block windows
+//! concurrently and checking ownership in parallel.
+//!
+//! The ledger is served in-process with a simulated network latency per request, so the
+//! benchmark measures how well the scan hides request latency and spreads ownership checks. The
+//! records of the genesis block are owned by the account scanning, so every block served holds
+//! records to decrypt.
+//!
+//! Run with `cargo bench --features snarkvm --bench scan`.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use avail_common::{
+    aleo_tools::{
+        api::{AleoAPIClient, RetryPolicy, Transport, TransportRequest, TransportResponse},
+        test_utils::MOCK_NODE_URL,
+    },
+    models::constants::TESTNET_PRIVATE_KEY,
+};
+use snarkvm::{ledger::block::Block, prelude::*};
+
+/// Number of blocks scanned in every run
+const BLOCKS: u32 = 1_000;
+/// Simulated round trip time of a request
+const LATENCY: Duration = Duration::from_millis(100);
+
+/// Transport answering every block range with copies of the genesis block after a delay
+#[derive(Debug)]
+struct SlowLedger {
+    genesis: Block<Testnet3>,
+}
+
+impl Transport for SlowLedger {
+    fn send(&self, request: &TransportRequest) -> Result<TransportResponse> {
+        thread::sleep(LATENCY);
+        let query = request.url.split_once('?').map(|(_, query)| query);
+        let mut range = (0u32, 0u32);
+        for pair in query.unwrap_or_default().split('&') {
+            match pair.split_once('=') {
+                Some(("start", value)) => range.0 = value.parse()?,
+                Some(("end", value)) => range.1 = value.parse()?,
+                _ => (),
+            }
+        }
+        let blocks = vec![&self.genesis; range.1.saturating_sub(range.0) as usize];
+        Ok(TransportResponse::new(200, serde_json::to_string(&blocks)?))
+    }
+}
+
+fn client(concurrency: usize) -> Result<AleoAPIClient<Testnet3>> {
+    AleoAPIClient::<Testnet3>::builder()
+        .base_url(MOCK_NODE_URL)
+        .transport(SlowLedger {
+            genesis: Block::from_bytes_le(Testnet3::genesis_bytes())?,
+        })
+        .retry_policy(RetryPolicy::no_retry())
+        .scan_concurrency(concurrency)
+        .build()
+}
+
+/// The scan as it was before block windows were fetched concurrently
+fn sequential_scan(view_key: &ViewKey<Testnet3>) -> Result<(Duration, usize)> {
+    let client = client(1)?;
+    let address_x_coordinate = view_key.to_address().to_x_coordinate();
+    let started = Instant::now();
+    let mut records = vec![];
+    for start_height in (0..BLOCKS).step_by(50) {
+        let end_height = (start_height + 50).min(BLOCKS);
+        let records_iter = client
+            .get_blocks(start_height, end_height)?
+            .into_iter()
+            .flat_map(|block| block.into_records());
+        records.extend(records_iter.filter_map(|(commitment, record)| {
+            match record.is_owner_with_address_x_coordinate(view_key, &address_x_coordinate) {
+                true => Some((commitment, record)),
+                false => None,
+            }
+        }));
+    }
+    Ok((started.elapsed(), records.len()))
+}
+
+fn scan(concurrency: usize, view_key: &ViewKey<Testnet3>) -> Result<(Duration, usize)> {
+    let client = client(concurrency)?;
+    let started = Instant::now();
+    let records = client.scan(*view_key, 0..BLOCKS, None)?;
+    Ok((started.elapsed(), records.len()))
+}
+
+fn main() -> Result<()> {
+    let view_key = ViewKey::try_from(PrivateKey::<Testnet3>::from_str(TESTNET_PRIVATE_KEY)?)?;
+
+    let (sequential, expected_records) = sequential_scan(&view_key)?;
+    ensure!(expected_records > 0, "The scanning account owns no records");
+    println!("Original sequential scan of {BLOCKS} blocks: {sequential:?}");
+    for concurrency in [1, 2, 4, 8, 16] {
+        let (elapsed, records) = scan(concurrency, &view_key)?;
+        ensure!(records == expected_records, "Scans found different records");
+        println!(
+            "Scan of {BLOCKS} blocks with {concurrency} concurrent windows: {elapsed:?} ({:.1}x)",
+            sequential.as_secs_f64() / elapsed.as_secs_f64()
+        );
+    }
+    Ok(())
+}
@@ -1,6 +1,19 @@
 use std::marker::PhantomData;
 
+use app_dirs::AppInfo;
+
 pub mod api;
+pub mod coin_selection;
 pub mod encryptor;
+pub mod fee_estimator;
+pub mod import_graph;
 pub mod program_manager;
+pub mod scanner;
 pub mod test_utils;
+pub mod tracker;
+
+/// Application information locating the directories the library caches data in
+pub(crate) const APP_INFO: AppInfo = AppInfo {
+    name: "avail",
+    author: "AvailX",
+};
@@ -1,31 +1,75 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
 pub mod blocking;
 
+pub mod endpoints;
+pub use endpoints::*;
+
+pub mod program_cache;
+pub use program_cache::*;
+
+pub mod retry;
+pub use retry::*;
+
+pub mod spent;
+pub use spent::*;
+
+pub mod transport;
+pub use transport::*;
+
+#[cfg(feature = "async")]
+pub mod asynchronous;
+#[cfg(feature = "async")]
+pub use asynchronous::AsyncAleoAPIClient;
+
 use snarkvm::prelude::*;
 
 use super::*;
 
 /// Aleo API client for interacting with the Aleo Beacon API
+///
+/// A client may be configured with several endpoints serving the same network. Reads are routed
+/// to the healthiest endpoint and fail over to the next one on transport errors or retryable
+/// status codes. Programs fetched are cached, see [`ProgramCache`]. Clones of a client share the
+/// endpoint health statistics and the program cache.
 #[derive(Clone, Debug)]
 pub struct AleoAPIClient<N: Network> {
-    client: ureq::Agent,
-    base_url: String,
+    client: Arc<dyn Transport>,
+    endpoints: Arc<EndpointPool>,
     network_id: String,
+    retry_policy: RetryPolicy,
+    broadcast_fanout: usize,
+    scan_concurrency: usize,
+    spent_serial_numbers: Arc<RwLock<HashMap<Field<N>, N::TransitionID>>>,
+    program_cache: Arc<ProgramCache<N>>,
+    offline: bool,
     _network: PhantomData<N>,
 }
 
 impl<N: Network> AleoAPIClient<N> {
     pub fn new(base_url: &str, chain: &str) -> Result<Self> {
-        let client = ureq::Agent::new();
-        ensure!(
-            base_url.starts_with("http://") || base_url.starts_with("https://"),
-            "specified url {base_url} invalid, the base url must start with or https:// (or http:// if doing local development)"
-        );
-        Ok(AleoAPIClient {
-            client,
-            base_url: base_url.to_string(),
-            network_id: chain.to_string(),
-            _network: PhantomData,
-        })
+        Self::builder().base_url(base_url).network_id(chain).build()
+    }
+
+    /// Create a client which sends its requests through a custom transport
+    pub fn with_transport(
+        base_url: &str,
+        chain: &str,
+        transport: impl Transport + 'static,
+    ) -> Result<Self> {
+        Self::builder()
+            .base_url(base_url)
+            .network_id(chain)
+            .transport(transport)
+            .build()
+    }
+
+    /// Create a builder to configure the transport and retry behavior of a client
+    pub fn builder() -> AleoAPIClientBuilder<N> {
+        AleoAPIClientBuilder::new()
     }
 
     pub fn testnet3() -> Self {
@@ -36,13 +80,172 @@ impl<N: Network> AleoAPIClient<N> {
         Self::new(&format!("http://{}:{}", ip, port), "testnet3").unwrap()
     }
 
-    /// Get base URL
+    /// Get the base URL of the healthiest endpoint
     pub fn base_url(&self) -> &str {
-        &self.base_url
+        self.endpoints.best()
+    }
+
+    /// Get the base URLs of all configured endpoints
+    pub fn endpoints(&self) -> &[String] {
+        self.endpoints.urls()
+    }
+
+    /// Get the health statistics of all configured endpoints
+    pub fn endpoint_health(&self) -> Vec<(String, EndpointHealth)> {
+        self.endpoints.health()
     }
 
     /// Get network ID being interacted with
     pub fn network_id(&self) -> &str {
         &self.network_id
     }
+
+    /// Get the retry policy applied to requests
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Get the maximum number of requests sent at once while scanning the ledger or checking
+    /// whether records are spent
+    pub fn scan_concurrency(&self) -> usize {
+        self.scan_concurrency
+    }
+
+    /// Get the cache of the programs fetched by the client
+    pub fn program_cache(&self) -> &ProgramCache<N> {
+        &self.program_cache
+    }
+
+    /// Whether the client is offline, serving programs from its cache and failing every request
+    /// instead of sending it
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+}
+
+/// Builder for [`AleoAPIClient`]s. Defaults to the public testnet3 explorer API, a ureq based
+/// transport and the default [`RetryPolicy`].
+#[derive(Debug)]
+pub struct AleoAPIClientBuilder<N: Network> {
+    base_url: String,
+    additional_endpoints: Vec<String>,
+    broadcast_fanout: usize,
+    scan_concurrency: usize,
+    network_id: String,
+    transport: Option<Arc<dyn Transport>>,
+    retry_policy: RetryPolicy,
+    program_cache: Option<ProgramCache<N>>,
+    offline: bool,
+    _network: PhantomData<N>,
+}
+
+impl<N: Network> AleoAPIClientBuilder<N> {
+    pub fn new() -> Self {
+        Self {
+            base_url: "https://api.explorer.aleo.org/v1".to_string(),
+            additional_endpoints: vec![],
+            broadcast_fanout: 1,
+            scan_concurrency: 4,
+            network_id: "testnet3".to_string(),
+            transport: None,
+            retry_policy: RetryPolicy::default(),
+            program_cache: None,
+            offline: false,
+            _network: PhantomData,
+        }
+    }
+
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Add an endpoint to fail over to when the preferred endpoints are unavailable
+    pub fn endpoint(mut self, url: &str) -> Self {
+        self.additional_endpoints.push(url.to_string());
+        self
+    }
+
+    /// Broadcast transactions to the given number of healthiest endpoints at once
+    pub fn broadcast_fanout(mut self, fanout: usize) -> Self {
+        self.broadcast_fanout = fanout;
+        self
+    }
+
+    /// Send up to the given number of requests at once while scanning the ledger or checking
+    /// whether records are spent
+    pub fn scan_concurrency(mut self, concurrency: usize) -> Self {
+        self.scan_concurrency = concurrency;
+        self
+    }
+
+    pub fn network_id(mut self, network_id: &str) -> Self {
+        self.network_id = network_id.to_string();
+        self
+    }
+
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Cache the programs fetched in the given cache instead of in memory only
+    pub fn program_cache(mut self, program_cache: ProgramCache<N>) -> Self {
+        self.program_cache = Some(program_cache);
+        self
+    }
+
+    /// Never send requests to the network, only serving the programs of the program cache
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    pub fn build(self) -> Result<AleoAPIClient<N>> {
+        let mut urls: Vec<String> = vec![];
+        for url in std::iter::once(self.base_url).chain(self.additional_endpoints) {
+            let url = url.trim_end_matches('/').to_string();
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        let endpoints = EndpointPool::new(urls)?;
+        ensure!(
+            self.broadcast_fanout > 0,
+            "Transactions must be broadcast to at least one endpoint"
+        );
+        ensure!(
+            self.scan_concurrency > 0,
+            "At least one request must be sent at a time while scanning"
+        );
+        ensure!(
+            self.retry_policy.max_attempts > 0,
+            "The retry policy must allow at least one attempt"
+        );
+        Ok(AleoAPIClient {
+            client: self
+                .transport
+                .unwrap_or_else(|| Arc::new(UreqTransport::new())),
+            endpoints: Arc::new(endpoints),
+            network_id: self.network_id,
+            retry_policy: self.retry_policy,
+            broadcast_fanout: self.broadcast_fanout,
+            scan_concurrency: self.scan_concurrency,
+            spent_serial_numbers: Default::default(),
+            program_cache: Arc::new(self.program_cache.unwrap_or_default()),
+            offline: self.offline,
+            _network: PhantomData,
+        })
+    }
+}
+
+impl<N: Network> Default for AleoAPIClientBuilder<N> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
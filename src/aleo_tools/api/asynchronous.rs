@@ -0,0 +1,978 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the Aleo SDK library.
+
+// The Aleo SDK library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo SDK library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo SDK library. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{collections::HashMap, ops::Range, time::Instant};
+
+use serde::de::DeserializeOwned;
+
+use super::*;
+use snarkvm::{circuit::prelude::IndexMap, ledger::block::*};
+
+use crate::aleo_tools::{import_graph::ImportGraph, program_manager::Credits};
+
+/// Asynchronous Aleo API client for interacting with the Aleo Beacon API from within an async
+/// runtime. It exposes the same endpoints as the blocking [`AleoAPIClient`], and like it routes
+/// requests to the healthiest of its endpoints, failing over to the others and retrying failed
+/// requests as configured by its [`RetryPolicy`].
+#[derive(Clone, Debug)]
+pub struct AsyncAleoAPIClient<N: Network> {
+    client: reqwest::Client,
+    endpoints: Arc<EndpointPool>,
+    network_id: String,
+    retry_policy: RetryPolicy,
+    scan_concurrency: usize,
+    spent_serial_numbers: Arc<RwLock<HashMap<Field<N>, N::TransitionID>>>,
+    _network: PhantomData<N>,
+}
+
+#[allow(clippy::type_complexity)]
+impl<N: Network> AsyncAleoAPIClient<N> {
+    pub fn new(base_url: &str, chain: &str) -> Result<Self> {
+        let endpoints = EndpointPool::new(vec![base_url.trim_end_matches('/').to_string()])?;
+        Ok(AsyncAleoAPIClient {
+            client: reqwest::Client::new(),
+            endpoints: Arc::new(endpoints),
+            network_id: chain.to_string(),
+            retry_policy: RetryPolicy::default(),
+            scan_concurrency: 4,
+            spent_serial_numbers: Default::default(),
+            _network: PhantomData,
+        })
+    }
+
+    pub fn testnet3() -> Self {
+        Self::new("https://api.explorer.aleo.org/v1", "testnet3").unwrap()
+    }
+
+    pub fn local_testnet3(port: &str, ip: &str) -> Self {
+        Self::new(&format!("http://{}:{}", ip, port), "testnet3").unwrap()
+    }
+
+    /// Add endpoints to fail over to when the preferred endpoints are unavailable
+    pub fn with_endpoints(mut self, urls: &[&str]) -> Result<Self> {
+        let mut endpoints = self.endpoints.urls().to_vec();
+        for url in urls {
+            let url = url.trim_end_matches('/').to_string();
+            if !endpoints.contains(&url) {
+                endpoints.push(url);
+            }
+        }
+        self.endpoints = Arc::new(EndpointPool::new(endpoints)?);
+        Ok(self)
+    }
+
+    /// Set the retry policy applied to requests
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Set the number of spent status lookups sent at once, at least one
+    pub fn with_scan_concurrency(mut self, concurrency: usize) -> Self {
+        self.scan_concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Get the base URL of the healthiest endpoint
+    pub fn base_url(&self) -> &str {
+        self.endpoints.best()
+    }
+
+    /// Get the base URLs of all configured endpoints
+    pub fn endpoints(&self) -> &[String] {
+        self.endpoints.urls()
+    }
+
+    /// Get the health statistics of all configured endpoints
+    pub fn endpoint_health(&self) -> Vec<(String, EndpointHealth)> {
+        self.endpoints.health()
+    }
+
+    /// Get network ID being interacted with
+    pub fn network_id(&self) -> &str {
+        &self.network_id
+    }
+
+    /// Get the retry policy applied to requests
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Get the number of spent status lookups sent at once
+    pub fn scan_concurrency(&self) -> usize {
+        self.scan_concurrency
+    }
+
+    /// Send a request to one endpoint, retrying transport failures and retryable status codes as
+    /// configured by the retry policy. Responses for which `is_answer` holds are returned as they
+    /// are. The outcome of every attempt is recorded in the health statistics of the endpoint.
+    async fn send_to(
+        &self,
+        endpoint: usize,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<&str>,
+        is_answer: fn(&TransportResponse) -> bool,
+    ) -> Result<TransportResponse> {
+        let url = format!("{}{path}", self.endpoints.url(endpoint));
+        let started = Instant::now();
+        let mut attempt = 1;
+        loop {
+            let result = self
+                .send_once(endpoint, method.clone(), &url, body, started, is_answer)
+                .await;
+            let retryable = match &result {
+                Ok(response) => {
+                    !is_answer(response) && self.retry_policy.is_retryable_status(response.status)
+                }
+                Err(_) => true,
+            };
+            match self.retry_policy.next_retry(attempt, started) {
+                Some(delay) if retryable => {
+                    tracing::debug!("Retrying request to {url} in {delay:?}");
+                    tokio::time::sleep(delay).await
+                }
+                _ => return result,
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Send a single attempt of a request, recording its outcome in the health statistics of the
+    /// endpoint
+    async fn send_once(
+        &self,
+        endpoint: usize,
+        method: reqwest::Method,
+        url: &str,
+        body: Option<&str>,
+        started: Instant,
+        is_answer: fn(&TransportResponse) -> bool,
+    ) -> Result<TransportResponse> {
+        let mut request = self.client.request(method, url);
+        if let Some(body) = body {
+            request = request
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.to_string());
+        }
+        if let Some(timeout) = self.retry_policy.attempt_timeout(started) {
+            request = request.timeout(timeout);
+        }
+
+        let sent = Instant::now();
+        let result = match request.send().await {
+            Ok(response) => {
+                let status = response.status().as_u16();
+                match response.text().await {
+                    Ok(body) => Ok(TransportResponse::new(status, body)),
+                    Err(error) => Err(anyhow!("{url}: {error}")),
+                }
+            }
+            Err(error) => Err(anyhow!("{url}: {error}")),
+        };
+        match &result {
+            Ok(response)
+                if is_answer(response)
+                    || !self.retry_policy.is_retryable_status(response.status) =>
+            {
+                self.endpoints.record_success(endpoint, sent.elapsed())
+            }
+            _ => self.endpoints.record_failure(endpoint),
+        }
+        result
+    }
+
+    /// Perform a GET request for the given path and return the response body, starting with the
+    /// healthiest endpoint and failing over to the others while requests fail with transport
+    /// errors or retryable status codes
+    async fn get_text(&self, path: &str) -> Result<String> {
+        Ok(self.get_with(path, |_| false).await?.into_string())
+    }
+
+    /// Perform a GET request like [`AsyncAleoAPIClient::get_text`], additionally accepting the
+    /// non-success responses for which `is_answer` holds, e.g. a node reporting that an item does
+    /// not exist
+    async fn get_with(
+        &self,
+        path: &str,
+        is_answer: fn(&TransportResponse) -> bool,
+    ) -> Result<TransportResponse> {
+        let mut last_error = None;
+        for endpoint in self.endpoints.ranked() {
+            match self
+                .send_to(endpoint, reqwest::Method::GET, path, None, is_answer)
+                .await
+            {
+                Ok(response) if response.is_success() || is_answer(&response) => {
+                    return Ok(response)
+                }
+                Ok(response) => {
+                    let url = format!("{}{path}", self.endpoints.url(endpoint));
+                    let error =
+                        anyhow!("{url}: status code {}: {}", response.status, response.body);
+                    if !self.retry_policy.is_retryable_status(response.status) {
+                        return Err(error);
+                    }
+                    last_error = Some(error);
+                }
+                Err(error) => last_error = Some(error),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("No endpoints configured")))
+    }
+
+    /// Perform a GET request for the given path and deserialize the JSON response body
+    async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let body = self.get_text(path).await?;
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    /// Get the latest block height
+    pub async fn latest_height(&self) -> Result<u32> {
+        let path = format!("/{}/latest/height", self.network_id);
+        match self.get_json(&path).await {
+            Ok(height) => Ok(height),
+            Err(error) => bail!("Failed to parse the latest block height: {error}"),
+        }
+    }
+
+    /// Get the latest block hash
+    pub async fn latest_hash(&self) -> Result<N::BlockHash> {
+        let path = format!("/{}/latest/hash", self.network_id);
+        match self.get_json(&path).await {
+            Ok(hash) => Ok(hash),
+            Err(error) => bail!("Failed to parse the latest block hash: {error}"),
+        }
+    }
+
+    /// Get the latest block
+    pub async fn latest_block(&self) -> Result<Block<N>> {
+        let path = format!("/{}/latest/block", self.network_id);
+        match self.get_json(&path).await {
+            Ok(block) => Ok(block),
+            Err(error) => bail!("Failed to parse the latest block: {error}"),
+        }
+    }
+
+    /// Get the block matching the specific height from the network
+    pub async fn get_block(&self, height: u32) -> Result<Block<N>> {
+        let path = format!("/{}/block/{height}", self.network_id);
+        match self.get_json(&path).await {
+            Ok(block) => Ok(block),
+            Err(error) => bail!("Failed to parse block {height}: {error}"),
+        }
+    }
+
+    /// Get a range of blocks from the network (limited 50 blocks at a time)
+    pub async fn get_blocks(&self, start_height: u32, end_height: u32) -> Result<Vec<Block<N>>> {
+        if start_height >= end_height {
+            bail!("Start height must be less than end height");
+        } else if end_height - start_height > 50 {
+            bail!("Cannot request more than 50 blocks at a time");
+        }
+
+        let path = format!(
+            "/{}/blocks?start={start_height}&end={end_height}",
+            self.network_id
+        );
+        match self.get_json(&path).await {
+            Ok(blocks) => Ok(blocks),
+            Err(error) => {
+                bail!("Failed to parse blocks {start_height} (inclusive) to {end_height} (exclusive): {error}")
+            }
+        }
+    }
+
+    /// Retrieve a transaction by via its transaction id
+    pub async fn get_transaction(
+        &self,
+        transaction_id: N::TransactionID,
+    ) -> Result<Transaction<N>> {
+        let path = format!("/{}/transaction/{transaction_id}", self.network_id);
+        match self.get_json(&path).await {
+            Ok(transaction) => Ok(transaction),
+            Err(error) => bail!("Failed to parse transaction '{transaction_id}': {error}"),
+        }
+    }
+
+    /// Get pending transactions currently in the mempool.
+    pub async fn get_memory_pool_transactions(&self) -> Result<Vec<Transaction<N>>> {
+        let path = format!("/{}/memoryPool/transactions", self.network_id);
+        match self.get_json(&path).await {
+            Ok(transactions) => Ok(transactions),
+            Err(error) => bail!("Failed to parse memory pool transactions: {error}"),
+        }
+    }
+
+    /// Get a program from the network by its ID. This method will return an error if it does not exist.
+    pub async fn get_program(&self, program_id: impl TryInto<ProgramID<N>>) -> Result<Program<N>> {
+        // Prepare the program ID.
+        let program_id = program_id
+            .try_into()
+            .map_err(|_| anyhow!("Invalid program ID"))?;
+        // Perform the request.
+        let path = format!("/{}/program/{program_id}", self.network_id);
+        match self.get_json(&path).await {
+            Ok(program) => Ok(program),
+            Err(error) => bail!("Failed to parse program {program_id}: {error}"),
+        }
+    }
+
+    /// Resolve imports of a program in a depth-first-search order from a program id
+    pub async fn get_program_imports(
+        &self,
+        program_id: impl TryInto<ProgramID<N>>,
+    ) -> Result<IndexMap<ProgramID<N>, Program<N>>> {
+        let program = self.get_program(program_id).await?;
+        self.get_program_imports_from_source(&program).await
+    }
+
+    /// Resolve the imports of a program from its source code, in deployment order. Every import
+    /// is fetched once, and circular imports are reported as errors, see [`ImportGraph`].
+    pub async fn get_program_imports_from_source(
+        &self,
+        program: &Program<N>,
+    ) -> Result<IndexMap<ProgramID<N>, Program<N>>> {
+        // Fetch every program reachable from the imports once, since the import graph can only
+        // resolve programs synchronously
+        let mut fetched = HashMap::new();
+        let mut pending = program.imports().keys().copied().collect::<Vec<_>>();
+        while let Some(import_id) = pending.pop() {
+            if import_id == *program.id() || fetched.contains_key(&import_id) {
+                continue;
+            }
+            let import = self
+                .get_program(import_id)
+                .await
+                .map_err(|error| anyhow!("Failed to resolve import {import_id}: {error}"))?;
+            pending.extend(import.imports().keys().copied());
+            fetched.insert(import_id, import);
+        }
+        let graph = ImportGraph::build(program, |import_id| {
+            fetched
+                .get(import_id)
+                .cloned()
+                .ok_or_else(|| anyhow!("Program {import_id} was not fetched"))
+        })?;
+        Ok(graph.into_imports())
+    }
+
+    /// Get all mappings associated with a program.
+    pub async fn get_program_mappings(
+        &self,
+        program_id: impl TryInto<ProgramID<N>>,
+    ) -> Result<Vec<Identifier<N>>> {
+        // Prepare the program ID.
+        let program_id = program_id
+            .try_into()
+            .map_err(|_| anyhow!("Invalid program ID"))?;
+        // Perform the request.
+        let path = format!("/{}/program/{program_id}/mappings", self.network_id);
+        match self.get_json(&path).await {
+            Ok(program_mappings) => Ok(program_mappings),
+            Err(error) => bail!("Failed to parse program {program_id}: {error}"),
+        }
+    }
+
+    /// Get the current value of a mapping given a specific program, mapping name, and mapping key
+    ///
+    /// Missing keys are reported as a "Mapping not found" error, see
+    /// [`AsyncAleoAPIClient::find_mapping_value`] to read them as `None` instead.
+    pub async fn get_mapping_value(
+        &self,
+        program_id: impl TryInto<ProgramID<N>>,
+        mapping_name: impl TryInto<Identifier<N>>,
+        key: &str,
+    ) -> Result<Value<N>> {
+        // Prepare the key.
+        let key = Plaintext::<N>::from_str(key).map_err(|_| anyhow!("Invalid key"))?;
+        match self
+            .find_mapping_value(program_id, mapping_name, &key)
+            .await?
+        {
+            Some(value) => Ok(value),
+            None => bail!("Mapping not found"),
+        }
+    }
+
+    /// Get the current value of a mapping given a specific program, mapping name, and mapping key,
+    /// or `None` if the key is not present in the mapping
+    pub async fn find_mapping_value(
+        &self,
+        program_id: impl TryInto<ProgramID<N>>,
+        mapping_name: impl TryInto<Identifier<N>>,
+        key: &Plaintext<N>,
+    ) -> Result<Option<Value<N>>> {
+        // Prepare the program ID.
+        let program_id = program_id
+            .try_into()
+            .map_err(|_| anyhow!("Invalid program ID"))?;
+        // Prepare the mapping name.
+        let mapping_name = mapping_name
+            .try_into()
+            .map_err(|_| anyhow!("Invalid mapping name"))?;
+        // Perform the request. Nodes answer with `null` for keys which are not in the mapping.
+        let key = encode_path_segment(&key.to_string());
+        let path = format!(
+            "/{}/program/{program_id}/mapping/{mapping_name}/{key}",
+            self.network_id
+        );
+        match self.get_json(&path).await {
+            Ok(value) => Ok(value),
+            Err(error) => bail!("Failed to parse mapping value: {error}"),
+        }
+    }
+
+    pub async fn find_block_hash(&self, transaction_id: N::TransactionID) -> Result<N::BlockHash> {
+        let path = format!("/{}/find/blockHash/{transaction_id}", self.network_id);
+        match self.get_json(&path).await {
+            Ok(hash) => Ok(hash),
+            Err(error) => bail!("Failed to parse block hash: {error}"),
+        }
+    }
+
+    /// Returns the transition ID that contains the given `input ID` or `output ID`.
+    pub async fn find_transition_id(
+        &self,
+        input_or_output_id: Field<N>,
+    ) -> Result<N::TransitionID> {
+        let path = format!(
+            "/{}/find/transitionID/{input_or_output_id}",
+            self.network_id
+        );
+        match self.get_json(&path).await {
+            Ok(transition_id) => Ok(transition_id),
+            Err(error) => bail!("Failed to parse transition ID: {error}"),
+        }
+    }
+
+    /// Determine whether the records with the given commitments have been spent by the owner of
+    /// the private key, like [`AleoAPIClient::get_spent_status`].
+    ///
+    /// Lookups are sent concurrently, up to the scan concurrency of the client, and serial numbers
+    /// known to be spent are cached, so they are not looked up again by this client or its clones.
+    /// A status is returned for every record, in the order given.
+    pub async fn get_spent_status<R>(
+        &self,
+        private_key: &PrivateKey<N>,
+        records: &[(Field<N>, R)],
+    ) -> Result<Vec<SpentStatus<N>>> {
+        let serial_numbers = records
+            .iter()
+            .map(|(commitment, _)| {
+                Record::<N, Ciphertext<N>>::serial_number(*private_key, *commitment)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut statuses = Vec::with_capacity(serial_numbers.len());
+        for group in serial_numbers.chunks(self.scan_concurrency) {
+            let lookups = group
+                .iter()
+                .map(|serial_number| self.serial_number_status(*serial_number));
+            statuses.extend(futures::future::join_all(lookups).await);
+        }
+        Ok(statuses)
+    }
+
+    /// Forget the serial numbers known to be spent
+    pub fn clear_spent_cache(&self) {
+        self.spent_serial_numbers.write().unwrap().clear();
+    }
+
+    async fn serial_number_status(&self, serial_number: Field<N>) -> SpentStatus<N> {
+        if let Some(transition_id) = self
+            .spent_serial_numbers
+            .read()
+            .unwrap()
+            .get(&serial_number)
+        {
+            return SpentStatus::Spent(*transition_id);
+        }
+        let path = format!("/{}/find/transitionID/{serial_number}", self.network_id);
+        let status = match self
+            .get_with(&path, SpentStatus::<N>::is_transition_not_found)
+            .await
+        {
+            Ok(response) => SpentStatus::from_transition_lookup(response),
+            Err(error) => SpentStatus::Unknown(error.to_string()),
+        };
+        if let SpentStatus::Spent(transition_id) = status {
+            self.spent_serial_numbers
+                .write()
+                .unwrap()
+                .insert(serial_number, transition_id);
+        }
+        status
+    }
+
+    /// Scans the ledger for records that match the given view key.
+    pub async fn scan(
+        &self,
+        view_key: impl TryInto<ViewKey<N>>,
+        block_heights: Range<u32>,
+        max_records: Option<usize>,
+    ) -> Result<Vec<(Field<N>, Record<N, Ciphertext<N>>)>> {
+        // Prepare the view key.
+        let view_key = view_key
+            .try_into()
+            .map_err(|_| anyhow!("Invalid view key"))?;
+        // Compute the x-coordinate of the address.
+        let address_x_coordinate = view_key.to_address().to_x_coordinate();
+
+        // Prepare the starting block height, by rounding down to the nearest step of 50.
+        let start_block_height = block_heights.start - (block_heights.start % 50);
+        // Prepare the ending block height, by rounding up to the nearest step of 50.
+        let end_block_height = block_heights.end + (50 - (block_heights.end % 50));
+
+        // Initialize a vector for the records.
+        let mut records = Vec::new();
+
+        for start_height in (start_block_height..end_block_height).step_by(50) {
+            tracing::debug!(
+                "Searching blocks {} to {} for records...",
+                start_height,
+                end_block_height
+            );
+            if start_height >= block_heights.end {
+                break;
+            }
+            let end = start_height + 50;
+            let end_height = if end > block_heights.end {
+                block_heights.end
+            } else {
+                end
+            };
+
+            let records_iter = self
+                .get_blocks(start_height, end_height)
+                .await?
+                .into_iter()
+                .flat_map(|block| block.into_records());
+
+            // Filter the records by the view key.
+            records.extend(records_iter.filter_map(|(commitment, record)| {
+                match record.is_owner_with_address_x_coordinate(&view_key, &address_x_coordinate) {
+                    true => Some((commitment, record)),
+                    false => None,
+                }
+            }));
+
+            if records.len() >= max_records.unwrap_or(usize::MAX) {
+                break;
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// Search for unspent records in the ledger, from the latest block to the earliest. Fails if
+    /// it cannot be determined whether a record is spent, rather than leaving the record out.
+    pub async fn get_unspent_records(
+        &self,
+        private_key: &PrivateKey<N>,
+        block_heights: Range<u32>,
+        max_gates: Option<u64>,
+        specified_amounts: Option<&Vec<u64>>,
+    ) -> Result<Vec<(Field<N>, Record<N, Plaintext<N>>)>> {
+        let view_key = ViewKey::try_from(private_key)?;
+        let address_x_coordinate = view_key.to_address().to_x_coordinate();
+
+        let step_size = 49;
+        let required_amounts = if let Some(amounts) = specified_amounts {
+            ensure!(
+                !amounts.is_empty(),
+                "If specific amounts are specified, there must be one amount specified"
+            );
+            let mut required_amounts = amounts.clone();
+            required_amounts.sort_by(|a, b| b.cmp(a));
+            required_amounts
+        } else {
+            vec![]
+        };
+
+        ensure!(
+            block_heights.start < block_heights.end,
+            "The start block height must be less than the end block height"
+        );
+
+        // Initialize a vector for the records.
+        let mut records = vec![];
+
+        let mut total_gates = 0u64;
+        let mut end_height = block_heights.end;
+        let mut start_height = block_heights.end.saturating_sub(step_size);
+
+        for _ in (block_heights.start..block_heights.end).step_by(step_size as usize) {
+            tracing::debug!(
+                "Searching blocks {} to {} for records...",
+                start_height,
+                end_height
+            );
+            // Get blocks
+            let blocks = self.get_blocks(start_height, end_height).await?;
+
+            // Search in reverse order from the latest block to the earliest block
+            end_height = start_height;
+            start_height = start_height.saturating_sub(step_size);
+            if start_height < block_heights.start {
+                start_height = block_heights.start
+            };
+
+            // Filter the records by the view key, and keep the unspent ones
+            let owned = blocks
+                .into_iter()
+                .flat_map(|block| block.into_records())
+                .filter(|(_, record)| {
+                    record.is_owner_with_address_x_coordinate(&view_key, &address_x_coordinate)
+                })
+                .collect::<Vec<_>>();
+            let statuses = self.get_spent_status(private_key, &owned).await?;
+            for ((commitment, record), status) in owned.into_iter().zip(statuses) {
+                match status {
+                    SpentStatus::Spent(_) => continue,
+                    SpentStatus::Unspent => (),
+                    SpentStatus::Unknown(reason) => {
+                        bail!("Failed to determine whether record {commitment} is spent: {reason}")
+                    }
+                }
+                if let Ok(record) = record.decrypt(&view_key) {
+                    total_gates += record.microcredits().unwrap_or(0);
+                    records.push((commitment, record));
+                }
+            }
+            // If a maximum number of gates is specified, stop searching when the total gates
+            // exceeds the specified limit
+            if max_gates.is_some() && total_gates >= max_gates.unwrap() {
+                break;
+            }
+            // If a list of specified amounts is specified, stop searching when records matching
+            // those amounts are found
+            if !required_amounts.is_empty() {
+                records.sort_by(|(_, first), (_, second)| {
+                    second
+                        .microcredits()
+                        .unwrap_or(0)
+                        .cmp(&first.microcredits().unwrap_or(0))
+                });
+                let mut found_indices = std::collections::HashSet::<usize>::new();
+                required_amounts.iter().for_each(|amount| {
+                    for (pos, (_, found_record)) in records.iter().enumerate() {
+                        let found_amount = found_record.microcredits().unwrap_or(0);
+                        if !found_indices.contains(&pos) && found_amount >= *amount {
+                            found_indices.insert(pos);
+                        }
+                    }
+                });
+                if found_indices.len() >= required_amounts.len() {
+                    let found_records = records[0..required_amounts.len()].to_vec();
+                    return Ok(found_records);
+                }
+            }
+        }
+        if !required_amounts.is_empty() {
+            bail!(
+                "Could not find enough records with the specified amounts, consider splitting records into smaller amounts"
+            );
+        }
+        Ok(records)
+    }
+
+    /// Broadcast a deploy or execute transaction to the Aleo network, starting with the healthiest
+    /// endpoint and failing over to the others
+    pub async fn transaction_broadcast(&self, transaction: Transaction<N>) -> Result<String> {
+        let path = format!("/{}/transaction/broadcast", self.network_id);
+        let transaction_id = transaction.id();
+        let body = serde_json::to_string(&transaction)?;
+
+        let mut errors = vec![];
+        for endpoint in self.endpoints.ranked() {
+            match self
+                .broadcast_to(endpoint, &path, &body, &transaction_id)
+                .await
+            {
+                Ok(response) => return Ok(response),
+                Err(error) => errors.push(error),
+            }
+        }
+        let error_message = errors.join(", ");
+
+        match transaction {
+            Transaction::Deploy(..) => {
+                bail!("❌ Failed to deploy program to {}", error_message)
+            }
+            Transaction::Execute(..) => {
+                bail!("❌ Failed to broadcast execution to {}", error_message)
+            }
+            Transaction::Fee(..) => {
+                bail!("❌ Failed to broadcast fee execution to {}", error_message)
+            }
+        }
+    }
+
+    /// Broadcast a serialized transaction to one endpoint. Broadcasts are retried like reads, but
+    /// since a failed attempt may still have reached the node, the transaction is looked up by
+    /// its ID before it is sent again. Failures are returned as `{url}: {reason}` messages.
+    async fn broadcast_to(
+        &self,
+        endpoint: usize,
+        path: &str,
+        body: &str,
+        transaction_id: &N::TransactionID,
+    ) -> std::result::Result<String, String> {
+        let url = format!("{}{path}", self.endpoints.url(endpoint));
+        let started = Instant::now();
+        let mut attempt = 1;
+        loop {
+            let (retryable, error_message) = match self
+                .send_once(
+                    endpoint,
+                    reqwest::Method::POST,
+                    &url,
+                    Some(body),
+                    started,
+                    |_| false,
+                )
+                .await
+            {
+                Ok(response) if response.is_success() => return Ok(response.into_string()),
+                Ok(response) => (
+                    self.retry_policy.is_retryable_status(response.status),
+                    format!("(status code {}: {:?})", response.status, response.body),
+                ),
+                Err(error) => (true, format!("({error})")),
+            };
+            match self.retry_policy.next_retry(attempt, started) {
+                Some(delay) if retryable => tokio::time::sleep(delay).await,
+                _ => return Err(format!("{url}: {error_message}")),
+            }
+            if self.is_transaction_known(transaction_id).await {
+                return serde_json::to_string(transaction_id).map_err(|error| error.to_string());
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Check if a transaction is known to the node, either confirmed or in the memory pool
+    async fn is_transaction_known(&self, transaction_id: &N::TransactionID) -> bool {
+        if self.get_transaction(*transaction_id).await.is_ok() {
+            return true;
+        }
+        self.get_memory_pool_transactions()
+            .await
+            .map(|transactions| {
+                transactions
+                    .iter()
+                    .any(|transaction| transaction.id() == *transaction_id)
+            })
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aleo_tools::test_utils::{
+        MockLedger, MockNodeServer, MULTIPLY_IMPORT_PROGRAM, MULTIPLY_PROGRAM,
+    };
+    use crate::models::constants::{TESTNET3_ADDRESS, TESTNET_ADDRESS, TESTNET_PRIVATE_KEY};
+
+    fn program(name: &str, imports: &[&str]) -> Program<Testnet3> {
+        let imports = imports
+            .iter()
+            .map(|import| format!("import {import}.aleo;\n"))
+            .collect::<String>();
+        Program::from_str(&format!(
+            "{imports}
+program {name}.aleo;
+
+function main:
+    input r0 as u32.public;
+    output r0 as u32.public;
+"
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_async_api_get_blocks() {
+        let client = AsyncAleoAPIClient::<Testnet3>::testnet3();
+        let blocks = client.get_blocks(0, 3).await.unwrap();
+
+        // Check height matches
+        assert_eq!(blocks[0].height(), 0);
+        assert_eq!(blocks[1].height(), 1);
+        assert_eq!(blocks[2].height(), 2);
+
+        // Check block hashes
+        assert_eq!(blocks[1].previous_hash(), blocks[0].hash());
+        assert_eq!(blocks[2].previous_hash(), blocks[1].hash());
+    }
+
+    #[tokio::test]
+    async fn test_async_import_resolution() {
+        let client = AsyncAleoAPIClient::<Testnet3>::testnet3();
+        let imports = client
+            .get_program_imports("imported_add_mul.aleo")
+            .await
+            .unwrap();
+        let id1 = ProgramID::<Testnet3>::from_str("multiply_test.aleo").unwrap();
+        let id2 = ProgramID::<Testnet3>::from_str("double_test.aleo").unwrap();
+        let id3 = ProgramID::<Testnet3>::from_str("addition_test.aleo").unwrap();
+
+        assert!(imports.contains_key(&id1));
+        assert!(imports.contains_key(&id2));
+        assert!(imports.contains_key(&id3));
+        assert_eq!(imports.keys().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_async_import_resolution_offline() {
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_program(Program::from_str(MULTIPLY_PROGRAM).unwrap());
+        ledger.add_program(Program::from_str(MULTIPLY_IMPORT_PROGRAM).unwrap());
+        ledger.add_program(program("first", &["second"]));
+        ledger.add_program(program("second", &["first"]));
+        let server = MockNodeServer::start(ledger).unwrap();
+        let client = AsyncAleoAPIClient::<Testnet3>::new(&server.base_url(), "testnet3").unwrap();
+
+        let imports = client
+            .get_program_imports("double_test.aleo")
+            .await
+            .unwrap();
+        let multiply_id = ProgramID::<Testnet3>::from_str("multiply_test.aleo").unwrap();
+        assert!(imports.contains_key(&multiply_id));
+        assert_eq!(imports.len(), 1);
+
+        // Ensure circular imports are reported with their path instead of recursing forever
+        let error = client
+            .get_program_imports_from_source(&program("top", &["first"]))
+            .await
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .ends_with("first.aleo -> second.aleo -> first.aleo"));
+    }
+
+    #[tokio::test]
+    async fn test_async_client_serves_mock_node() {
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_genesis_block().unwrap();
+        ledger.add_program(Program::credits().unwrap());
+        ledger
+            .set_mapping_value(
+                "credits.aleo",
+                "account",
+                TESTNET_ADDRESS,
+                Value::from_str("5u64").unwrap(),
+            )
+            .unwrap();
+        let server = MockNodeServer::start(ledger).unwrap();
+        let client = AsyncAleoAPIClient::<Testnet3>::new(&server.base_url(), "testnet3").unwrap();
+
+        assert_eq!(client.latest_height().await.unwrap(), 0);
+        let blocks = client.get_blocks(0, 1).await.unwrap();
+        assert_eq!(blocks[0].height(), 0);
+        assert_eq!(
+            client
+                .get_mapping_value("credits.aleo", "account", TESTNET_ADDRESS)
+                .await
+                .unwrap(),
+            Value::from_str("5u64").unwrap()
+        );
+        assert!(client
+            .get_mapping_value("credits.aleo", "account", TESTNET3_ADDRESS)
+            .await
+            .is_err());
+
+        // Ensure transactions are broadcast to the node
+        let transaction = blocks[0]
+            .transactions()
+            .iter()
+            .next()
+            .unwrap()
+            .transaction();
+        client
+            .transaction_broadcast(transaction.clone())
+            .await
+            .unwrap();
+        assert_eq!(server.ledger().broadcast_transactions().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_async_requests_fail_over_to_healthy_endpoints() {
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_program(Program::from_str(MULTIPLY_PROGRAM).unwrap());
+        let server = MockNodeServer::start(ledger).unwrap();
+        let client = AsyncAleoAPIClient::<Testnet3>::new("http://127.0.0.1:1", "testnet3")
+            .unwrap()
+            .with_endpoints(&[&server.base_url()])
+            .unwrap()
+            .with_retry_policy(RetryPolicy::no_retry());
+
+        assert!(client.get_program("multiply_test.aleo").await.is_ok());
+        let health = client.endpoint_health();
+        assert_eq!(health[0].1.failures, 1);
+        assert_eq!(health[1].1.successes, 1);
+
+        // Ensure the healthy endpoint is preferred afterwards
+        assert_eq!(client.base_url(), server.base_url());
+        assert!(client.get_program("multiply_test.aleo").await.is_ok());
+        assert_eq!(client.endpoint_health()[0].1.failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_async_spent_status_matches_the_blocking_client() {
+        let private_key = PrivateKey::<Testnet3>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_genesis_block().unwrap();
+        let blocking_client = ledger.client();
+        let server = MockNodeServer::start(ledger.clone()).unwrap();
+        let client = AsyncAleoAPIClient::<Testnet3>::new(&server.base_url(), "testnet3").unwrap();
+
+        let records = blocking_client
+            .get_unspent_records(&private_key, 0..1, None, None)
+            .unwrap();
+        assert!(!records.is_empty());
+        let statuses = client
+            .get_spent_status(&private_key, &records)
+            .await
+            .unwrap();
+        assert!(statuses.iter().all(SpentStatus::is_unspent));
+
+        // Ensure spent records are left out by both clients alike
+        let serial_number =
+            Record::<Testnet3, Ciphertext<Testnet3>>::serial_number(private_key, records[0].0)
+                .unwrap();
+        let transition_id = <Testnet3 as Network>::TransitionID::from(Field::from_u64(1));
+        ledger.add_transition_id(serial_number, transition_id);
+        let unspent = client
+            .get_unspent_records(&private_key, 0..1, None, None)
+            .await
+            .unwrap();
+        assert_eq!(unspent.len(), records.len() - 1);
+        assert_eq!(
+            unspent,
+            blocking_client
+                .get_unspent_records(&private_key, 0..1, None, None)
+                .unwrap()
+        );
+        let statuses = client
+            .get_spent_status(&private_key, &records)
+            .await
+            .unwrap();
+        assert_eq!(statuses[0], SpentStatus::Spent(transition_id));
+    }
+}
@@ -14,20 +14,102 @@
 // You should have received a copy of the GNU General Public License
 // along with the Aleo SDK library. If not, see <https://www.gnu.org/licenses/>.
 
-use std::ops::Range;
+use std::{
+    ops::{ControlFlow, Range},
+    time::Instant,
+};
 
 use super::*;
 use snarkvm::{circuit::prelude::IndexMap, ledger::block::*};
 
-use crate::aleo_tools::program_manager::Credits;
+use rayon::prelude::*;
+
+use crate::aleo_tools::{
+    import_graph::ImportGraph,
+    program_manager::Credits,
+    scanner::{find_owned_records, find_records_by_account, ScanEvent, ScanProgress},
+};
 
-#[cfg(not(feature = "async"))]
 #[allow(clippy::type_complexity)]
 impl<N: Network> AleoAPIClient<N> {
+    /// Send a request to one endpoint through the configured transport, retrying transport
+    /// failures and retryable status codes as configured by the retry policy. Responses for which
+    /// `is_answer` holds are returned as they are. The outcome of every attempt is recorded in the
+    /// health statistics of the endpoint.
+    fn send_to(
+        &self,
+        endpoint: usize,
+        request: &TransportRequest,
+        is_answer: &dyn Fn(&TransportResponse) -> bool,
+    ) -> Result<TransportResponse> {
+        ensure!(
+            !self.offline,
+            "The client is offline, {} was not requested",
+            request.url
+        );
+        let started = Instant::now();
+        let mut attempt = 1;
+        loop {
+            let timeout = self.retry_policy.attempt_timeout(started);
+            let sent = Instant::now();
+            let result = self.client.send(&request.clone().with_timeout(timeout));
+            let retryable = match &result {
+                Ok(response) => {
+                    !is_answer(response) && self.retry_policy.is_retryable_status(response.status)
+                }
+                Err(_) => true,
+            };
+            if retryable {
+                self.endpoints.record_failure(endpoint);
+            } else {
+                self.endpoints.record_success(endpoint, sent.elapsed());
+            }
+            match self.retry_policy.next_retry(attempt, started) {
+                Some(delay) if retryable => std::thread::sleep(delay),
+                _ => return result,
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Send a GET request for the given path, starting with the healthiest endpoint and failing
+    /// over to the others while requests fail with transport errors or retryable status codes
+    fn get(&self, path: &str) -> Result<TransportResponse> {
+        self.get_with(path, &|_| false)
+    }
+
+    /// Send a GET request like [`AleoAPIClient::get`], additionally accepting the non-success
+    /// responses for which `is_answer` holds, e.g. a node reporting that an item does not exist
+    fn get_with(
+        &self,
+        path: &str,
+        is_answer: &dyn Fn(&TransportResponse) -> bool,
+    ) -> Result<TransportResponse> {
+        let mut last_error = None;
+        for endpoint in self.endpoints.ranked() {
+            let url = format!("{}{path}", self.endpoints.url(endpoint));
+            match self.send_to(endpoint, &TransportRequest::get(&url), is_answer) {
+                Ok(response) if response.is_success() || is_answer(&response) => {
+                    return Ok(response)
+                }
+                Ok(response) => {
+                    let error =
+                        anyhow!("{url}: status code {}: {}", response.status, response.body);
+                    if !self.retry_policy.is_retryable_status(response.status) {
+                        return Err(error);
+                    }
+                    last_error = Some(error);
+                }
+                Err(error) => last_error = Some(error),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("No endpoints configured")))
+    }
+
     /// Get the latest block height
     pub fn latest_height(&self) -> Result<u32> {
-        let url = format!("{}/{}/latest/height", self.base_url, self.network_id);
-        match self.client.get(&url).call()?.into_json() {
+        let path = format!("/{}/latest/height", self.network_id);
+        match self.get(&path)?.into_json() {
             Ok(height) => Ok(height),
             Err(error) => bail!("Failed to parse the latest block height: {error}"),
         }
@@ -35,8 +117,8 @@ impl<N: Network> AleoAPIClient<N> {
 
     /// Get the latest block hash
     pub fn latest_hash(&self) -> Result<N::BlockHash> {
-        let url = format!("{}/{}/latest/hash", self.base_url, self.network_id);
-        match self.client.get(&url).call()?.into_json() {
+        let path = format!("/{}/latest/hash", self.network_id);
+        match self.get(&path)?.into_json() {
             Ok(hash) => Ok(hash),
             Err(error) => bail!("Failed to parse the latest block hash: {error}"),
         }
@@ -44,8 +126,8 @@ impl<N: Network> AleoAPIClient<N> {
 
     /// Get the latest block
     pub fn latest_block(&self) -> Result<Block<N>> {
-        let url = format!("{}/{}/latest/block", self.base_url, self.network_id);
-        match self.client.get(&url).call()?.into_json() {
+        let path = format!("/{}/latest/block", self.network_id);
+        match self.get(&path)?.into_json() {
             Ok(block) => Ok(block),
             Err(error) => bail!("Failed to parse the latest block: {error}"),
         }
@@ -53,13 +135,22 @@ impl<N: Network> AleoAPIClient<N> {
 
     /// Get the block matching the specific height from the network
     pub fn get_block(&self, height: u32) -> Result<Block<N>> {
-        let url = format!("{}/{}/block/{height}", self.base_url, self.network_id);
-        match self.client.get(&url).call()?.into_json() {
+        let path = format!("/{}/block/{height}", self.network_id);
+        match self.get(&path)?.into_json() {
             Ok(block) => Ok(block),
             Err(error) => bail!("Failed to parse block {height}: {error}"),
         }
     }
 
+    /// Get the block with the given hash from the network
+    pub fn get_block_by_hash(&self, hash: N::BlockHash) -> Result<Block<N>> {
+        let path = format!("/{}/block/{hash}", self.network_id);
+        match self.get(&path)?.into_json() {
+            Ok(block) => Ok(block),
+            Err(error) => bail!("Failed to parse block {hash}: {error}"),
+        }
+    }
+
     /// Get a range of blocks from the network (limited 50 blocks at a time)
     pub fn get_blocks(&self, start_height: u32, end_height: u32) -> Result<Vec<Block<N>>> {
         if start_height >= end_height {
@@ -68,11 +159,11 @@ impl<N: Network> AleoAPIClient<N> {
             bail!("Cannot request more than 50 blocks at a time");
         }
 
-        let url = format!(
-            "{}/{}/blocks?start={start_height}&end={end_height}",
-            self.base_url, self.network_id
+        let path = format!(
+            "/{}/blocks?start={start_height}&end={end_height}",
+            self.network_id
         );
-        match self.client.get(&url).call()?.into_json() {
+        match self.get(&path)?.into_json() {
             Ok(blocks) => Ok(blocks),
             Err(error) => {
                 bail!("Failed to parse blocks {start_height} (inclusive) to {end_height} (exclusive): {error}")
@@ -82,11 +173,8 @@ impl<N: Network> AleoAPIClient<N> {
 
     /// Retrieve a transaction by via its transaction id
     pub fn get_transaction(&self, transaction_id: N::TransactionID) -> Result<Transaction<N>> {
-        let url = format!(
-            "{}/{}/transaction/{transaction_id}",
-            self.base_url, self.network_id
-        );
-        match self.client.get(&url).call()?.into_json() {
+        let path = format!("/{}/transaction/{transaction_id}", self.network_id);
+        match self.get(&path)?.into_json() {
             Ok(transaction) => Ok(transaction),
             Err(error) => bail!("Failed to parse transaction '{transaction_id}': {error}"),
         }
@@ -94,28 +182,41 @@ impl<N: Network> AleoAPIClient<N> {
 
     /// Get pending transactions currently in the mempool.
     pub fn get_memory_pool_transactions(&self) -> Result<Vec<Transaction<N>>> {
-        let url = format!(
-            "{}/{}/memoryPool/transactions",
-            self.base_url, self.network_id
-        );
-        match self.client.get(&url).call()?.into_json() {
+        let path = format!("/{}/memoryPool/transactions", self.network_id);
+        match self.get(&path)?.into_json() {
             Ok(transactions) => Ok(transactions),
             Err(error) => bail!("Failed to parse memory pool transactions: {error}"),
         }
     }
 
     /// Get a program from the network by its ID. This method will return an error if it does not exist.
+    ///
+    /// Programs are served from the program cache of the client once they have been fetched.
     pub fn get_program(&self, program_id: impl TryInto<ProgramID<N>>) -> Result<Program<N>> {
         // Prepare the program ID.
         let program_id = program_id
             .try_into()
             .map_err(|_| anyhow!("Invalid program ID"))?;
+        if let Some(program) = self.program_cache.get(&self.network_id, &program_id)? {
+            return Ok(program);
+        }
+        ensure!(
+            !self.offline,
+            "Program {program_id} is not cached and the client is offline"
+        );
         // Perform the request.
-        let url = format!("{}/{}/program/{program_id}", self.base_url, self.network_id);
-        match self.client.get(&url).call()?.into_json() {
-            Ok(program) => Ok(program),
+        let path = format!("/{}/program/{program_id}", self.network_id);
+        let program: Program<N> = match self.get(&path)?.into_json() {
+            Ok(program) => program,
             Err(error) => bail!("Failed to parse program {program_id}: {error}"),
-        }
+        };
+        ensure!(
+            program.id() == &program_id,
+            "The network returned program {} instead of {program_id}",
+            program.id()
+        );
+        self.program_cache.insert(&self.network_id, &program)?;
+        Ok(program)
     }
 
     /// Resolve imports of a program in a depth-first-search order from a program id
@@ -127,27 +228,14 @@ impl<N: Network> AleoAPIClient<N> {
         self.get_program_imports_from_source(&program)
     }
 
-    /// Resolve imports of a program in a depth-first-search order from program source code
+    /// Resolve imports of a program in a depth-first-search order from program source code, so
+    /// every import comes after its own imports. Every import is fetched once, and circular
+    /// imports are reported as errors, see [`ImportGraph`].
     pub fn get_program_imports_from_source(
         &self,
         program: &Program<N>,
     ) -> Result<IndexMap<ProgramID<N>, Program<N>>> {
-        let mut found_imports = IndexMap::new();
-        for (import_id, _) in program.imports().iter() {
-            let imported_program = self.get_program(import_id)?;
-            let nested_imports = self.get_program_imports_from_source(&imported_program)?;
-            for (id, import) in nested_imports.into_iter() {
-                found_imports
-                    .contains_key(&id)
-                    .then(|| anyhow!("Circular dependency discovered in program imports"));
-                found_imports.insert(id, import);
-            }
-            found_imports
-                .contains_key(import_id)
-                .then(|| anyhow!("Circular dependency discovered in program imports"));
-            found_imports.insert(*import_id, imported_program);
-        }
-        Ok(found_imports)
+        Ok(ImportGraph::build(program, |import_id| self.get_program(import_id))?.into_imports())
     }
 
     /// Get all mappings associated with a program.
@@ -160,23 +248,39 @@ impl<N: Network> AleoAPIClient<N> {
             .try_into()
             .map_err(|_| anyhow!("Invalid program ID"))?;
         // Perform the request.
-        let url = format!(
-            "{}/{}/program/{program_id}/mappings",
-            self.base_url, self.network_id
-        );
-        match self.client.get(&url).call()?.into_json() {
+        let path = format!("/{}/program/{program_id}/mappings", self.network_id);
+        match self.get(&path)?.into_json() {
             Ok(program_mappings) => Ok(program_mappings),
             Err(error) => bail!("Failed to parse program {program_id}: {error}"),
         }
     }
 
     /// Get the current value of a mapping given a specific program, mapping name, and mapping key
+    ///
+    /// Missing keys are reported as a "Mapping not found" error, see
+    /// [`AleoAPIClient::find_mapping_value`] to read them as `None` instead.
     pub fn get_mapping_value(
         &self,
         program_id: impl TryInto<ProgramID<N>>,
         mapping_name: impl TryInto<Identifier<N>>,
         key: &str,
     ) -> Result<Value<N>> {
+        // Prepare the key.
+        let key = Plaintext::<N>::from_str(key).map_err(|_| anyhow!("Invalid key"))?;
+        match self.find_mapping_value(program_id, mapping_name, &key)? {
+            Some(value) => Ok(value),
+            None => bail!("Mapping not found"),
+        }
+    }
+
+    /// Get the current value of a mapping given a specific program, mapping name, and mapping key,
+    /// or `None` if the key is not present in the mapping
+    pub fn find_mapping_value(
+        &self,
+        program_id: impl TryInto<ProgramID<N>>,
+        mapping_name: impl TryInto<Identifier<N>>,
+        key: &Plaintext<N>,
+    ) -> Result<Option<Value<N>>> {
         // Prepare the program ID.
         let program_id = program_id
             .try_into()
@@ -185,29 +289,21 @@ impl<N: Network> AleoAPIClient<N> {
         let mapping_name = mapping_name
             .try_into()
             .map_err(|_| anyhow!("Invalid mapping name"))?;
-        // Prepare the key.
-        //let key = key.try_into().map_err(|_| anyhow!("Invalid key"))?;
-        // Perform the request.
-        let url = format!(
-            "{}/{}/program/{program_id}/mapping/{mapping_name}/{key}",
-            self.base_url, self.network_id
+        // Perform the request. Nodes answer with `null` for keys which are not in the mapping.
+        let key = encode_path_segment(&key.to_string());
+        let path = format!(
+            "/{}/program/{program_id}/mapping/{mapping_name}/{key}",
+            self.network_id
         );
-
-        match self.client.get(&url).call()?.into_json() {
-            Ok(transition_id) => Ok(transition_id),
-            Err(error) => match error.to_string().as_str().contains("invalid type: null") {
-                true => bail!("Mapping not found"),
-                false => bail!("Failed to parse mapping value: {error}"),
-            },
+        match self.get(&path)?.into_json() {
+            Ok(value) => Ok(value),
+            Err(error) => bail!("Failed to parse mapping value: {error}"),
         }
     }
 
     pub fn find_block_hash(&self, transaction_id: N::TransactionID) -> Result<N::BlockHash> {
-        let url = format!(
-            "{}/{}/find/blockHash/{transaction_id}",
-            self.base_url, self.network_id
-        );
-        match self.client.get(&url).call()?.into_json() {
+        let path = format!("/{}/find/blockHash/{transaction_id}", self.network_id);
+        match self.get(&path)?.into_json() {
             Ok(hash) => Ok(hash),
             Err(error) => bail!("Failed to parse block hash: {error}"),
         }
@@ -215,69 +311,245 @@ impl<N: Network> AleoAPIClient<N> {
 
     /// Returns the transition ID that contains the given `input ID` or `output ID`.
     pub fn find_transition_id(&self, input_or_output_id: Field<N>) -> Result<N::TransitionID> {
-        let url = format!(
-            "{}/{}/find/transitionID/{input_or_output_id}",
-            self.base_url, self.network_id
+        let path = format!(
+            "/{}/find/transitionID/{input_or_output_id}",
+            self.network_id
         );
-        match self.client.get(&url).call()?.into_json() {
+        match self.get(&path)?.into_json() {
             Ok(transition_id) => Ok(transition_id),
             Err(error) => bail!("Failed to parse transition ID: {error}"),
         }
     }
 
+    /// Determine whether the records with the given commitments have been spent by the owner of
+    /// the private key, by looking up the transitions consuming their serial numbers.
+    ///
+    /// Lookups are sent concurrently, up to the scan concurrency of the client, and serial numbers
+    /// known to be spent are cached, so they are not looked up again by this client or its clones.
+    /// A status is returned for every record, in the order given.
+    pub fn get_spent_status<R: Sync>(
+        &self,
+        private_key: &PrivateKey<N>,
+        records: &[(Field<N>, R)],
+    ) -> Result<Vec<SpentStatus<N>>> {
+        let serial_numbers = records
+            .par_iter()
+            .map(|(commitment, _)| {
+                Record::<N, Ciphertext<N>>::serial_number(*private_key, *commitment)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut statuses = Vec::with_capacity(serial_numbers.len());
+        for group in serial_numbers.chunks(self.scan_concurrency) {
+            std::thread::scope(|scope| {
+                let handles = group
+                    .iter()
+                    .map(|serial_number| scope.spawn(|| self.serial_number_status(*serial_number)))
+                    .collect::<Vec<_>>();
+                for handle in handles {
+                    statuses.push(handle.join().unwrap_or_else(|_| {
+                        SpentStatus::Unknown("Spent status thread panicked".to_string())
+                    }));
+                }
+            });
+        }
+        Ok(statuses)
+    }
+
+    /// Forget the serial numbers known to be spent
+    pub fn clear_spent_cache(&self) {
+        self.spent_serial_numbers.write().unwrap().clear();
+    }
+
+    fn serial_number_status(&self, serial_number: Field<N>) -> SpentStatus<N> {
+        if let Some(transition_id) = self
+            .spent_serial_numbers
+            .read()
+            .unwrap()
+            .get(&serial_number)
+        {
+            return SpentStatus::Spent(*transition_id);
+        }
+        let path = format!("/{}/find/transitionID/{serial_number}", self.network_id);
+        let status = match self.get_with(&path, &SpentStatus::<N>::is_transition_not_found) {
+            Ok(response) => SpentStatus::from_transition_lookup(response),
+            Err(error) => SpentStatus::Unknown(error.to_string()),
+        };
+        if let SpentStatus::Spent(transition_id) = status {
+            self.spent_serial_numbers
+                .write()
+                .unwrap()
+                .insert(serial_number, transition_id);
+        }
+        status
+    }
+
+    /// Retrieve the blocks of several height ranges of at most 50 blocks each, fetching up to
+    /// `scan_concurrency` ranges at once. The blocks are returned in the order of the ranges.
+    pub fn get_block_windows(&self, windows: &[Range<u32>]) -> Result<Vec<Vec<Block<N>>>> {
+        let mut blocks = Vec::with_capacity(windows.len());
+        for group in windows.chunks(self.scan_concurrency) {
+            let results = std::thread::scope(|scope| {
+                let handles = group
+                    .iter()
+                    .map(|window| scope.spawn(|| self.get_blocks(window.start, window.end)))
+                    .collect::<Vec<_>>();
+                handles
+                    .into_iter()
+                    .map(|handle| {
+                        handle
+                            .join()
+                            .unwrap_or_else(|_| Err(anyhow!("Block request thread panicked")))
+                    })
+                    .collect::<Vec<_>>()
+            });
+            for result in results {
+                blocks.push(result?);
+            }
+        }
+        Ok(blocks)
+    }
+
     /// Scans the ledger for records that match the given view key.
+    ///
+    /// Block windows are fetched concurrently, up to the scan concurrency of the client, and the
+    /// ownership of their records is checked in parallel. Records are returned in ledger order.
     pub fn scan(
         &self,
         view_key: impl TryInto<ViewKey<N>>,
         block_heights: Range<u32>,
         max_records: Option<usize>,
+    ) -> Result<Vec<(Field<N>, Record<N, Ciphertext<N>>)>> {
+        self.scan_with(view_key, block_heights, max_records, |_| {
+            ControlFlow::Continue(())
+        })
+    }
+
+    /// Scans the ledger for records that match the given view key like [`AleoAPIClient::scan`].
+    /// Every record found and every window of blocks scanned is reported to the callback, which
+    /// may stop the scan by returning [`ControlFlow::Break`]. Returns the records found until then.
+    pub fn scan_with(
+        &self,
+        view_key: impl TryInto<ViewKey<N>>,
+        block_heights: Range<u32>,
+        max_records: Option<usize>,
+        mut callback: impl FnMut(ScanEvent<N>) -> ControlFlow<()>,
     ) -> Result<Vec<(Field<N>, Record<N, Ciphertext<N>>)>> {
         // Prepare the view key.
         let view_key = view_key
             .try_into()
             .map_err(|_| anyhow!("Invalid view key"))?;
-        // Compute the x-coordinate of the address.
-        let address_x_coordinate = view_key.to_address().to_x_coordinate();
 
         // Prepare the starting block height, by rounding down to the nearest step of 50.
         let start_block_height = block_heights.start - (block_heights.start % 50);
-        // Prepare the ending block height, by rounding up to the nearest step of 50.
-        let end_block_height = block_heights.end + (50 - (block_heights.end % 50));
+        // Prepare the windows of 50 blocks to search.
+        let windows = (start_block_height..block_heights.end)
+            .step_by(50)
+            .map(|start| start..(start + 50).min(block_heights.end))
+            .collect::<Vec<_>>();
 
         // Initialize a vector for the records.
         let mut records = Vec::new();
+        let mut progress = ScanProgress {
+            start_height: start_block_height,
+            scanned_height: start_block_height,
+            end_height: block_heights.end,
+            records_found: 0,
+        };
 
-        for start_height in (start_block_height..end_block_height).step_by(50) {
-            println!(
-                "Searching blocks {} to {} for records...",
-                start_height, end_block_height
-            );
-            if start_height >= block_heights.end {
-                break;
+        for group in windows.chunks(self.scan_concurrency) {
+            for (window, blocks) in group.iter().zip(self.get_block_windows(group)?) {
+                // Filter the records by the view key.
+                for (height, commitment, record) in find_owned_records(blocks, &view_key) {
+                    records.push((commitment, record.clone()));
+                    progress.records_found += 1;
+                    let event = ScanEvent::Record {
+                        height,
+                        commitment,
+                        record,
+                    };
+                    if callback(event).is_break() {
+                        return Ok(records);
+                    }
+                }
+                progress.scanned_height = window.end;
+                if callback(ScanEvent::Progress(progress)).is_break()
+                    || records.len() >= max_records.unwrap_or(usize::MAX)
+                {
+                    return Ok(records);
+                }
             }
-            let end = start_height + 50;
-            let end_height = if end > block_heights.end {
-                block_heights.end
-            } else {
-                end
-            };
+        }
 
-            // Prepare the URL.
-            let records_iter = self
-                .get_blocks(start_height, end_height)?
-                .into_iter()
-                .flat_map(|block| block.into_records());
+        Ok(records)
+    }
 
-            // Filter the records by the view key.
-            records.extend(records_iter.filter_map(|(commitment, record)| {
-                match record.is_owner_with_address_x_coordinate(&view_key, &address_x_coordinate) {
-                    true => Some((commitment, record)),
-                    false => None,
-                }
-            }));
+    /// Scans the ledger for the records of several accounts at once, downloading every block a
+    /// single time. Records are returned per address, in ledger order, with an entry for every
+    /// account even if no records were found for it.
+    pub fn scan_accounts(
+        &self,
+        view_keys: &[ViewKey<N>],
+        block_heights: Range<u32>,
+    ) -> Result<IndexMap<Address<N>, Vec<(Field<N>, Record<N, Ciphertext<N>>)>>> {
+        self.scan_accounts_with(view_keys, block_heights, |_| ControlFlow::Continue(()))
+    }
 
-            if records.len() >= max_records.unwrap_or(usize::MAX) {
-                break;
+    /// Scans the ledger for the records of several accounts like
+    /// [`AleoAPIClient::scan_accounts`]. Every window of blocks scanned is reported to the
+    /// callback as a [`ScanEvent::Progress`] counting the records of all accounts, and the
+    /// callback may stop the scan by returning [`ControlFlow::Break`]. Returns the records found
+    /// until then.
+    pub fn scan_accounts_with(
+        &self,
+        view_keys: &[ViewKey<N>],
+        block_heights: Range<u32>,
+        mut callback: impl FnMut(ScanEvent<N>) -> ControlFlow<()>,
+    ) -> Result<IndexMap<Address<N>, Vec<(Field<N>, Record<N, Ciphertext<N>>)>>> {
+        ensure!(
+            !view_keys.is_empty(),
+            "At least one view key must be specified"
+        );
+
+        // Deduplicate the accounts, so every record is attributed to a single address.
+        let mut accounts = IndexMap::new();
+        for view_key in view_keys {
+            accounts.entry(view_key.to_address()).or_insert(*view_key);
+        }
+        let view_keys = accounts.values().copied().collect::<Vec<_>>();
+        let mut records = accounts
+            .keys()
+            .map(|address| (*address, vec![]))
+            .collect::<IndexMap<_, Vec<_>>>();
+
+        // Prepare the windows of 50 blocks to search.
+        let windows = (block_heights.start..block_heights.end)
+            .step_by(50)
+            .map(|start| start..(start + 50).min(block_heights.end))
+            .collect::<Vec<_>>();
+
+        let mut progress = ScanProgress {
+            start_height: block_heights.start,
+            scanned_height: block_heights.start,
+            end_height: block_heights.end,
+            records_found: 0,
+        };
+
+        for group in windows.chunks(self.scan_concurrency) {
+            for (window, blocks) in group.iter().zip(self.get_block_windows(group)?) {
+                let found = find_records_by_account(blocks, &view_keys);
+                for (bucket, found) in records.values_mut().zip(found) {
+                    progress.records_found += found.len();
+                    bucket.extend(
+                        found
+                            .into_iter()
+                            .map(|(_, commitment, record)| (commitment, record)),
+                    );
+                }
+                progress.scanned_height = window.end;
+                if callback(ScanEvent::Progress(progress)).is_break() {
+                    return Ok(records);
+                }
             }
         }
 
@@ -285,6 +557,11 @@ impl<N: Network> AleoAPIClient<N> {
     }
 
     /// Search for unspent records in the ledger
+    ///
+    /// The ledger is searched from the latest block to the earliest. Block windows are fetched
+    /// concurrently, up to the scan concurrency of the client, and the records of every window
+    /// are checked for ownership, spent and decrypted in parallel. Fails if it cannot be determined
+    /// whether a record is spent, rather than leaving the record out.
     pub fn get_unspent_records(
         &self,
         private_key: &PrivateKey<N>,
@@ -293,7 +570,6 @@ impl<N: Network> AleoAPIClient<N> {
         specified_amounts: Option<&Vec<u64>>,
     ) -> Result<Vec<(Field<N>, Record<N, Plaintext<N>>)>> {
         let view_key = ViewKey::try_from(private_key)?;
-        let address_x_coordinate = view_key.to_address().to_x_coordinate();
 
         let step_size = 49;
         let required_amounts = if let Some(amounts) = specified_amounts {
@@ -313,78 +589,80 @@ impl<N: Network> AleoAPIClient<N> {
             "The start block height must be less than the end block height"
         );
 
+        // Prepare the windows to search, from the latest block to the earliest block
+        let mut windows = vec![];
+        let mut end_height = block_heights.end;
+        while end_height > block_heights.start {
+            let start_height = end_height
+                .saturating_sub(step_size)
+                .max(block_heights.start);
+            windows.push(start_height..end_height);
+            end_height = start_height;
+        }
+
         // Initialize a vector for the records.
         let mut records = vec![];
 
         let mut total_gates = 0u64;
-        let mut end_height = block_heights.end;
-        let mut start_height = block_heights.end.saturating_sub(step_size);
-
-        for _ in (block_heights.start..block_heights.end).step_by(step_size as usize) {
-            println!(
-                "Searching blocks {} to {} for records...",
-                start_height, end_height
+        for group in windows.chunks(self.scan_concurrency) {
+            tracing::debug!(
+                "Searching blocks {} to {} for unspent records...",
+                group[group.len() - 1].start,
+                group[0].end
             );
-            // Get blocks
-            let records_iter = self
-                .get_blocks(start_height, end_height)?
-                .into_iter()
-                .flat_map(|block| block.into_records());
-
-            // Search in reverse order from the latest block to the earliest block
-            end_height = start_height;
-            start_height = start_height.saturating_sub(step_size);
-            if start_height < block_heights.start {
-                start_height = block_heights.start
-            };
-            // Filter the records by the view key.
-            records.extend(records_iter.filter_map(|(commitment, record)| {
-                match record.is_owner_with_address_x_coordinate(&view_key, &address_x_coordinate) {
-                    true => {
-                        let sn =
-                            Record::<N, Ciphertext<N>>::serial_number(*private_key, commitment)
-                                .ok()?;
-                        if self.find_transition_id(sn).is_err() {
-                            let record = record.decrypt(&view_key);
-                            if let Ok(record) = record {
-                                total_gates += record.microcredits().unwrap_or(0);
-                                Some((commitment, record))
-                            } else {
-                                None
-                            }
-                        } else {
-                            None
-                        }
+            for blocks in self.get_block_windows(group)? {
+                // Filter the records by the view key, and keep the unspent ones.
+                let owned = find_owned_records(blocks, &view_key)
+                    .into_iter()
+                    .map(|(_, commitment, record)| (commitment, record))
+                    .collect::<Vec<_>>();
+                let statuses = self.get_spent_status(private_key, &owned)?;
+                for ((commitment, _), status) in owned.iter().zip(&statuses) {
+                    if let SpentStatus::Unknown(reason) = status {
+                        bail!("Failed to determine whether record {commitment} is spent: {reason}");
                     }
-                    false => None,
                 }
-            }));
-            // If a maximum number of gates is specified, stop searching when the total gates
-            // exceeds the specified limit
-            if max_gates.is_some() && total_gates >= max_gates.unwrap() {
-                break;
-            }
-            // If a list of specified amounts is specified, stop searching when records matching
-            // those amounts are found
-            if !required_amounts.is_empty() {
-                records.sort_by(|(_, first), (_, second)| {
-                    second
-                        .microcredits()
-                        .unwrap_or(0)
-                        .cmp(&first.microcredits().unwrap_or(0))
-                });
-                let mut found_indices = std::collections::HashSet::<usize>::new();
-                required_amounts.iter().for_each(|amount| {
-                    for (pos, (_, found_record)) in records.iter().enumerate() {
-                        let found_amount = found_record.microcredits().unwrap_or(0);
-                        if !found_indices.contains(&pos) && found_amount >= *amount {
-                            found_indices.insert(pos);
+                let unspent = owned
+                    .into_par_iter()
+                    .zip(statuses)
+                    .filter_map(|((commitment, record), status)| match status {
+                        SpentStatus::Unspent => Some((commitment, record.decrypt(&view_key).ok()?)),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                total_gates += unspent
+                    .iter()
+                    .map(|(_, record)| record.microcredits().unwrap_or(0))
+                    .sum::<u64>();
+                records.extend(unspent);
+
+                // If a maximum number of gates is specified, stop searching when the total gates
+                // exceeds the specified limit
+                if max_gates.is_some() && total_gates >= max_gates.unwrap() {
+                    return Ok(records);
+                }
+                // If a list of specified amounts is specified, stop searching when records matching
+                // those amounts are found
+                if !required_amounts.is_empty() {
+                    records.sort_by(|(_, first), (_, second)| {
+                        second
+                            .microcredits()
+                            .unwrap_or(0)
+                            .cmp(&first.microcredits().unwrap_or(0))
+                    });
+                    let mut found_indices = std::collections::HashSet::<usize>::new();
+                    required_amounts.iter().for_each(|amount| {
+                        for (pos, (_, found_record)) in records.iter().enumerate() {
+                            let found_amount = found_record.microcredits().unwrap_or(0);
+                            if !found_indices.contains(&pos) && found_amount >= *amount {
+                                found_indices.insert(pos);
+                            }
                         }
+                    });
+                    if found_indices.len() >= required_amounts.len() {
+                        let found_records = records[0..required_amounts.len()].to_vec();
+                        return Ok(found_records);
                     }
-                });
-                if found_indices.len() >= required_amounts.len() {
-                    let found_records = records[0..required_amounts.len()].to_vec();
-                    return Ok(found_records);
                 }
             }
         }
@@ -398,50 +676,215 @@ impl<N: Network> AleoAPIClient<N> {
 
     /// Broadcast a deploy or execute transaction to the Aleo network
     pub fn transaction_broadcast(&self, transaction: Transaction<N>) -> Result<String> {
-        let url = format!(
-            "{}/{}/transaction/broadcast",
-            self.base_url, self.network_id
+        ensure!(
+            !self.offline,
+            "The client is offline, transaction {} was not broadcast",
+            transaction.id()
         );
-        match self.client.post(&url).send_json(&transaction) {
-            Ok(response) => match response.into_string() {
-                Ok(success_response) => Ok(success_response),
-                Err(error) => bail!("❌ Transaction response was malformed {}", error),
-            },
-            Err(error) => {
-                let error_message = match error {
-                    ureq::Error::Status(code, response) => {
-                        format!("(status code {code}: {:?})", response.into_string()?)
-                    }
-                    ureq::Error::Transport(err) => format!("({err})"),
-                };
+        let path = format!("/{}/transaction/broadcast", self.network_id);
+        let transaction_id = transaction.id();
+        let body = serde_json::to_string(&transaction)?;
 
-                match transaction {
-                    Transaction::Deploy(..) => {
-                        bail!("❌ Failed to deploy program to {}: {}", &url, error_message)
-                    }
-                    Transaction::Execute(..) => {
-                        bail!(
-                            "❌ Failed to broadcast execution to {}: {}",
-                            &url,
-                            error_message
-                        )
-                    }
-                    Transaction::Fee(..) => {
-                        bail!(
-                            "❌ Failed to broadcast fee execution to {}: {}",
-                            &url,
-                            error_message
-                        )
+        // Send the transaction to the healthiest endpoints at once, then fail over to the others
+        let endpoints = self.endpoints.ranked();
+        let fanout = self.broadcast_fanout.min(endpoints.len());
+        let (targets, fallbacks) = endpoints.split_at(fanout);
+        let mut errors = vec![];
+        let (path, body) = (path.as_str(), body.as_str());
+        let results = std::thread::scope(|scope| {
+            let handles = targets
+                .iter()
+                .map(|&endpoint| {
+                    let transaction_id = &transaction_id;
+                    scope.spawn(move || self.broadcast_to(endpoint, path, body, transaction_id))
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|_| Err("(broadcast thread panicked)".to_string()))
+                })
+                .collect::<Vec<_>>()
+        });
+        for result in results {
+            match result {
+                Ok(response) => return Ok(response),
+                Err(error) => errors.push(error),
+            }
+        }
+        for endpoint in fallbacks {
+            match self.broadcast_to(*endpoint, path, body, &transaction_id) {
+                Ok(response) => return Ok(response),
+                Err(error) => errors.push(error),
+            }
+        }
+        let error_message = errors.join(", ");
+
+        match transaction {
+            Transaction::Deploy(..) => {
+                bail!("❌ Failed to deploy program to {}", error_message)
+            }
+            Transaction::Execute(..) => {
+                bail!("❌ Failed to broadcast execution to {}", error_message)
+            }
+            Transaction::Fee(..) => {
+                bail!("❌ Failed to broadcast fee execution to {}", error_message)
+            }
+        }
+    }
+
+    /// Broadcast a serialized transaction to one endpoint. Broadcasts are retried like reads, but
+    /// since a failed attempt may still have reached the node, the transaction is looked up by
+    /// its ID before it is sent again. Failures are returned as `{url}: {reason}` messages.
+    fn broadcast_to(
+        &self,
+        endpoint: usize,
+        path: &str,
+        body: &str,
+        transaction_id: &N::TransactionID,
+    ) -> std::result::Result<String, String> {
+        let url = format!("{}{path}", self.endpoints.url(endpoint));
+        let request = TransportRequest::post(&url, body.to_string());
+        let started = Instant::now();
+        let mut attempt = 1;
+        loop {
+            let timeout = self.retry_policy.attempt_timeout(started);
+            let sent = Instant::now();
+            let (retryable, error_message) =
+                match self.client.send(&request.clone().with_timeout(timeout)) {
+                    Ok(response) if response.is_success() => {
+                        self.endpoints.record_success(endpoint, sent.elapsed());
+                        return Ok(response.into_string());
                     }
-                }
+                    Ok(response) => (
+                        self.retry_policy.is_retryable_status(response.status),
+                        format!("(status code {}: {:?})", response.status, response.body),
+                    ),
+                    Err(error) => (true, format!("({error})")),
+                };
+            if retryable {
+                self.endpoints.record_failure(endpoint);
+            } else {
+                self.endpoints.record_success(endpoint, sent.elapsed());
+            }
+            match self.retry_policy.next_retry(attempt, started) {
+                Some(delay) if retryable => std::thread::sleep(delay),
+                _ => return Err(format!("{url}: {error_message}")),
+            }
+            if self.is_transaction_known(transaction_id) {
+                return serde_json::to_string(transaction_id).map_err(|error| error.to_string());
             }
+            attempt += 1;
         }
     }
+
+    /// Check if a transaction is known to the node, either confirmed or in the memory pool
+    fn is_transaction_known(&self, transaction_id: &N::TransactionID) -> bool {
+        self.get_transaction(*transaction_id).is_ok()
+            || self
+                .get_memory_pool_transactions()
+                .map(|transactions| {
+                    transactions
+                        .iter()
+                        .any(|transaction| transaction.id() == *transaction_id)
+                })
+                .unwrap_or(false)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aleo_tools::test_utils::{
+        MockLedger, MOCK_NODE_URL, MULTIPLY_IMPORT_PROGRAM, MULTIPLY_PROGRAM,
+    };
+    use crate::models::constants::{TESTNET3_ADDRESS, TESTNET_ADDRESS, TESTNET_PRIVATE_KEY};
+
+    use std::{
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    /// Transport answering the first requests with a service unavailable error
+    #[derive(Debug)]
+    struct FlakyTransport {
+        ledger: MockLedger<Testnet3>,
+        failures: AtomicU32,
+        requests: Arc<AtomicU32>,
+    }
+
+    impl FlakyTransport {
+        fn new(ledger: MockLedger<Testnet3>, failures: u32) -> (Self, Arc<AtomicU32>) {
+            let requests = Arc::new(AtomicU32::new(0));
+            let transport = Self {
+                ledger,
+                failures: AtomicU32::new(failures),
+                requests: requests.clone(),
+            };
+            (transport, requests)
+        }
+    }
+
+    impl Transport for FlakyTransport {
+        fn send(&self, request: &TransportRequest) -> Result<TransportResponse> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            let remaining_failures = self.failures.load(Ordering::SeqCst);
+            if remaining_failures > 0 {
+                self.failures
+                    .store(remaining_failures - 1, Ordering::SeqCst);
+                return Ok(TransportResponse::new(503, "Service unavailable"));
+            }
+            self.ledger.send(request)
+        }
+    }
+
+    /// Transport answering every request with the same error
+    #[derive(Debug)]
+    struct FailingTransport {
+        status: u16,
+        body: &'static str,
+    }
+
+    impl Transport for FailingTransport {
+        fn send(&self, _request: &TransportRequest) -> Result<TransportResponse> {
+            Ok(TransportResponse::new(self.status, self.body))
+        }
+    }
+
+    /// Transport serving the mock node, except for transition lookups which fail with a server error
+    #[derive(Debug)]
+    struct FailingLookupTransport {
+        ledger: MockLedger<Testnet3>,
+    }
+
+    impl Transport for FailingLookupTransport {
+        fn send(&self, request: &TransportRequest) -> Result<TransportResponse> {
+            if request.url.contains("/find/transitionID/") {
+                return Ok(TransportResponse::new(500, "Missing block store"));
+            }
+            self.ledger.send(request)
+        }
+    }
+
+    /// Transport failing to connect to every endpoint except the mock node
+    #[derive(Debug)]
+    struct PartitionedTransport {
+        ledger: MockLedger<Testnet3>,
+    }
+
+    impl Transport for PartitionedTransport {
+        fn send(&self, request: &TransportRequest) -> Result<TransportResponse> {
+            if !request.url.starts_with(MOCK_NODE_URL) {
+                bail!("Connection refused");
+            }
+            self.ledger.send(request)
+        }
+    }
 
     #[test]
     fn test_api_get_blocks() {
@@ -485,4 +928,363 @@ mod tests {
         assert!(imports.contains_key(&id3));
         assert_eq!(keys.len(), 3);
     }
+
+    #[test]
+    fn test_import_resolution_offline() {
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_program(Program::from_str(MULTIPLY_PROGRAM).unwrap());
+        ledger.add_program(Program::from_str(MULTIPLY_IMPORT_PROGRAM).unwrap());
+
+        let imports = ledger
+            .client()
+            .get_program_imports("double_test.aleo")
+            .unwrap();
+        let multiply_id = ProgramID::<Testnet3>::from_str("multiply_test.aleo").unwrap();
+        assert!(imports.contains_key(&multiply_id));
+        assert_eq!(imports.len(), 1);
+
+        // Ensure resolution fails if an import is not deployed
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_program(Program::from_str(MULTIPLY_IMPORT_PROGRAM).unwrap());
+        assert!(ledger
+            .client()
+            .get_program_imports("double_test.aleo")
+            .is_err());
+    }
+
+    #[test]
+    fn test_mappings_query_offline() {
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_program(Program::credits().unwrap());
+        ledger
+            .set_mapping_value(
+                "credits.aleo",
+                "account",
+                TESTNET_ADDRESS,
+                Value::from_str("100u64").unwrap(),
+            )
+            .unwrap();
+        let client = ledger.client();
+
+        let mappings = client.get_program_mappings("credits.aleo").unwrap();
+        assert!(mappings.contains(&Identifier::from_str("account").unwrap()));
+
+        let value = client
+            .get_mapping_value("credits.aleo", "account", TESTNET_ADDRESS)
+            .unwrap();
+        assert_eq!(value, Value::from_str("100u64").unwrap());
+
+        // Ensure missing keys are found as `None`, and produce errors when they are expected
+        let missing_key = Plaintext::<Testnet3>::from_str(TESTNET3_ADDRESS).unwrap();
+        assert_eq!(
+            client
+                .find_mapping_value("credits.aleo", "account", &missing_key)
+                .unwrap(),
+            None
+        );
+        assert!(client
+            .get_mapping_value("credits.aleo", "account", TESTNET3_ADDRESS)
+            .is_err());
+        assert!(client.get_program_mappings("hello.aleo").is_err());
+    }
+
+    #[test]
+    fn test_requests_are_retried() {
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_program(Program::from_str(MULTIPLY_PROGRAM).unwrap());
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            jitter: false,
+            ..Default::default()
+        };
+        let client = |transport: FlakyTransport| {
+            AleoAPIClient::<Testnet3>::builder()
+                .base_url(MOCK_NODE_URL)
+                .transport(transport)
+                .retry_policy(policy.clone())
+                .build()
+                .unwrap()
+        };
+
+        // Ensure transient failures are retried
+        let (transport, requests) = FlakyTransport::new(ledger.clone(), 2);
+        assert!(client(transport).get_program("multiply_test.aleo").is_ok());
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        // Ensure requests fail once all attempts are exhausted
+        let (transport, requests) = FlakyTransport::new(ledger.clone(), 3);
+        assert!(client(transport).get_program("multiply_test.aleo").is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        // Ensure non-retryable errors are not retried
+        let (transport, requests) = FlakyTransport::new(ledger, 0);
+        assert!(client(transport).get_program("hello.aleo").is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_requests_fail_over_to_healthy_endpoints() {
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_program(Program::from_str(MULTIPLY_PROGRAM).unwrap());
+        let client = AleoAPIClient::<Testnet3>::builder()
+            .base_url("http://down.node/")
+            .endpoint(MOCK_NODE_URL)
+            .transport(PartitionedTransport { ledger })
+            .retry_policy(RetryPolicy::no_retry())
+            .build()
+            .unwrap();
+        assert_eq!(client.endpoints(), ["http://down.node", MOCK_NODE_URL]);
+
+        // Ensure requests are served by the fallback endpoint while the first one is down
+        assert!(client.get_program("multiply_test.aleo").is_ok());
+        let health = client.endpoint_health();
+        assert_eq!(health[0].1.failures, 1);
+        assert_eq!(health[1].1.successes, 1);
+
+        // Ensure the healthy endpoint is preferred from then on
+        assert_eq!(client.base_url(), MOCK_NODE_URL);
+        assert!(client.get_program_mappings("multiply_test.aleo").is_ok());
+        assert_eq!(client.endpoint_health()[0].1.failures, 1);
+
+        // Ensure non-retryable errors are not failed over
+        assert!(client.get_program("hello.aleo").is_err());
+        assert_eq!(client.endpoint_health()[0].1.failures, 1);
+    }
+
+    #[test]
+    fn test_block_windows_are_returned_in_order() {
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_genesis_block().unwrap();
+        let client = AleoAPIClient::<Testnet3>::builder()
+            .base_url(MOCK_NODE_URL)
+            .transport(ledger)
+            .scan_concurrency(2)
+            .build()
+            .unwrap();
+
+        let windows = client
+            .get_block_windows(&[50..100, 0..50, 100..150, 0..1])
+            .unwrap();
+        let sizes = windows.iter().map(Vec::len).collect::<Vec<_>>();
+        assert_eq!(sizes, vec![0, 1, 0, 1]);
+        assert!(client.get_block_windows(&[0..100]).is_err());
+    }
+
+    #[test]
+    fn test_scan_accounts_buckets_records_per_address() {
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_genesis_block().unwrap();
+        let client = ledger.client();
+        let rng = &mut TestRng::default();
+        let first = ViewKey::try_from(PrivateKey::<Testnet3>::new(rng).unwrap()).unwrap();
+        let second = ViewKey::try_from(PrivateKey::<Testnet3>::new(rng).unwrap()).unwrap();
+
+        // Ensure every account gets a bucket, once, in the order given
+        let records = client.scan_accounts(&[first, second, first], 0..1).unwrap();
+        let addresses = records.keys().copied().collect::<Vec<_>>();
+        assert_eq!(addresses, vec![first.to_address(), second.to_address()]);
+
+        // Ensure the buckets match scanning every account on its own
+        for view_key in [first, second] {
+            let expected = client.scan(view_key, 0..1, None).unwrap();
+            assert_eq!(records[&view_key.to_address()], expected);
+        }
+        assert!(client.scan_accounts(&[], 0..1).is_err());
+    }
+
+    #[test]
+    fn test_scan_progress_is_reported_to_the_callback() {
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_genesis_block().unwrap();
+        let client = ledger.client();
+        let view_key =
+            ViewKey::try_from(PrivateKey::<Testnet3>::new(&mut TestRng::default()).unwrap())
+                .unwrap();
+
+        let mut events = vec![];
+        let records = client
+            .scan_with(view_key, 0..1, None, |event| {
+                events.push(event);
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+        assert!(records.is_empty());
+        assert!(matches!(
+            events.as_slice(),
+            [ScanEvent::Progress(progress)] if progress.is_complete()
+        ));
+
+        let mut progress = vec![];
+        client
+            .scan_accounts_with(&[view_key], 0..1, |event| {
+                progress.push(event);
+                ControlFlow::Break(())
+            })
+            .unwrap();
+        assert_eq!(progress.len(), 1);
+    }
+
+    #[test]
+    fn test_spent_status_is_checked_in_bulk() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<Testnet3>::new(rng).unwrap();
+        let spent = Field::<Testnet3>::rand(rng);
+        let unspent = Field::<Testnet3>::rand(rng);
+        let transition_id = <Testnet3 as Network>::TransitionID::from(Field::rand(rng));
+
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        let serial_number =
+            Record::<Testnet3, Ciphertext<Testnet3>>::serial_number(private_key, spent).unwrap();
+        ledger.add_transition_id(serial_number, transition_id);
+        let (transport, requests) = FlakyTransport::new(ledger, 0);
+        let client = AleoAPIClient::<Testnet3>::builder()
+            .base_url(MOCK_NODE_URL)
+            .transport(transport)
+            .build()
+            .unwrap();
+
+        // Ensure a status is returned for every record, in order, without retrying unspent lookups
+        let records = [(spent, ()), (unspent, ()), (spent, ())];
+        let statuses = client.get_spent_status(&private_key, &records).unwrap();
+        assert_eq!(
+            statuses,
+            vec![
+                SpentStatus::Spent(transition_id),
+                SpentStatus::Unspent,
+                SpentStatus::Spent(transition_id)
+            ]
+        );
+        let sent = requests.load(Ordering::SeqCst);
+        assert!(sent <= 3);
+
+        // Ensure spent serial numbers are cached, while unspent ones are looked up again
+        let statuses = client.get_spent_status(&private_key, &records).unwrap();
+        assert!(statuses[0].is_spent() && statuses[1].is_unspent());
+        assert_eq!(requests.load(Ordering::SeqCst), sent + 1);
+
+        // Ensure unreachable nodes produce unknown statuses
+        let client = AleoAPIClient::<Testnet3>::builder()
+            .base_url("http://down.node")
+            .transport(PartitionedTransport {
+                ledger: MockLedger::new("testnet3"),
+            })
+            .retry_policy(RetryPolicy::no_retry())
+            .build()
+            .unwrap();
+        let statuses = client.get_spent_status(&private_key, &records).unwrap();
+        assert!(matches!(statuses[1], SpentStatus::Unknown(_)));
+
+        // Ensure server errors are not mistaken for missing transitions
+        let client = AleoAPIClient::<Testnet3>::builder()
+            .base_url(MOCK_NODE_URL)
+            .transport(FailingTransport {
+                status: 500,
+                body: "Missing block store",
+            })
+            .retry_policy(RetryPolicy::no_retry())
+            .build()
+            .unwrap();
+        let statuses = client.get_spent_status(&private_key, &records).unwrap();
+        assert!(matches!(statuses[1], SpentStatus::Unknown(_)));
+
+        // Ensure the answer of snarkOS nodes to serial numbers no transition consumed is understood
+        let client = AleoAPIClient::<Testnet3>::builder()
+            .base_url(MOCK_NODE_URL)
+            .transport(FailingTransport {
+                status: 500,
+                body: "Something went wrong: Failed to find the transition ID for the given input or output ID '1field'",
+            })
+            .retry_policy(RetryPolicy::no_retry())
+            .build()
+            .unwrap();
+        let statuses = client.get_spent_status(&private_key, &records).unwrap();
+        assert!(statuses.iter().all(SpentStatus::is_unspent));
+    }
+
+    #[test]
+    fn test_unspent_records_are_never_dropped() {
+        let private_key = PrivateKey::<Testnet3>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_genesis_block().unwrap();
+        let client = ledger.client();
+
+        // Ensure spent records are left out
+        let records = client
+            .get_unspent_records(&private_key, 0..1, None, None)
+            .unwrap();
+        assert!(!records.is_empty());
+        let serial_number =
+            Record::<Testnet3, Ciphertext<Testnet3>>::serial_number(private_key, records[0].0)
+                .unwrap();
+        let transition_id = <Testnet3 as Network>::TransitionID::from(Field::from_u64(1));
+        ledger.add_transition_id(serial_number, transition_id);
+        let unspent = client
+            .get_unspent_records(&private_key, 0..1, None, None)
+            .unwrap();
+        assert_eq!(unspent.len(), records.len() - 1);
+
+        // Ensure records whose spent status is unknown fail the search instead of disappearing
+        let client = AleoAPIClient::<Testnet3>::builder()
+            .base_url(MOCK_NODE_URL)
+            .transport(FailingLookupTransport { ledger })
+            .retry_policy(RetryPolicy::no_retry())
+            .build()
+            .unwrap();
+        assert!(client
+            .get_unspent_records(&private_key, 0..1, None, None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_programs_are_served_from_the_cache() {
+        let directory = tempfile::tempdir().unwrap();
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_program(Program::from_str(MULTIPLY_PROGRAM).unwrap());
+        let (transport, requests) = FlakyTransport::new(ledger, 0);
+        let client = AleoAPIClient::<Testnet3>::builder()
+            .base_url(MOCK_NODE_URL)
+            .transport(transport)
+            .program_cache(ProgramCache::with_directory(directory.path()))
+            .build()
+            .unwrap();
+
+        // Ensure programs are fetched once
+        let program = client.get_program("multiply_test.aleo").unwrap();
+        assert_eq!(client.get_program("multiply_test.aleo").unwrap(), program);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // Ensure offline clients serve cached programs without sending requests
+        let (transport, requests) = FlakyTransport::new(MockLedger::new("testnet3"), 0);
+        let client = AleoAPIClient::<Testnet3>::builder()
+            .base_url(MOCK_NODE_URL)
+            .transport(transport)
+            .program_cache(ProgramCache::with_directory(directory.path()))
+            .offline(true)
+            .build()
+            .unwrap();
+        assert!(client.is_offline());
+        assert_eq!(client.get_program("multiply_test.aleo").unwrap(), program);
+        assert!(client.get_program("double_test.aleo").is_err());
+        assert!(client.latest_height().is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_offline_clients_refuse_to_broadcast() {
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        let (transport, requests) = FlakyTransport::new(ledger.clone(), 0);
+        let client = AleoAPIClient::<Testnet3>::builder()
+            .base_url(MOCK_NODE_URL)
+            .transport(transport)
+            .offline(true)
+            .build()
+            .unwrap();
+
+        let genesis = Block::<Testnet3>::from_bytes_le(Testnet3::genesis_bytes()).unwrap();
+        let transaction = genesis.transactions().iter().next().unwrap().transaction();
+        assert!(client.transaction_broadcast(transaction.clone()).is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+        assert!(ledger.broadcast_transactions().is_empty());
+    }
 }
@@ -0,0 +1,193 @@
+use std::{
+    sync::RwLock,
+    time::{Duration, Instant},
+};
+
+use snarkvm::prelude::{ensure, Result};
+
+/// Weight of the most recent observation in the moving averages of an endpoint's health
+const SMOOTHING_FACTOR: f64 = 0.2;
+/// Number of consecutive failures after which an endpoint is put in cool down
+const COOL_DOWN_THRESHOLD: u32 = 3;
+/// Time an endpoint is ranked last after reaching the consecutive failure threshold
+const COOL_DOWN_PERIOD: Duration = Duration::from_secs(30);
+
+/// Health statistics of a node endpoint, gathered from the requests sent to it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EndpointHealth {
+    /// Moving average of the response latency
+    pub latency: Option<Duration>,
+    /// Moving average of the share of failed requests, between 0 and 1
+    pub error_rate: f64,
+    pub successes: u64,
+    pub failures: u64,
+    pub consecutive_failures: u32,
+    pub last_failure: Option<Instant>,
+}
+
+impl EndpointHealth {
+    /// Score of the endpoint, lower is healthier. Endpoints which were never used score best so
+    /// they are probed early on, while endpoints which only ever failed score worst.
+    pub fn score(&self) -> f64 {
+        let latency_ms = match self.latency {
+            Some(latency) => latency.as_secs_f64() * 1000.0,
+            None if self.failures > 0 => return f64::INFINITY,
+            None => 0.0,
+        };
+        (latency_ms + 1.0) * (1.0 + 10.0 * self.error_rate)
+    }
+
+    /// Whether the endpoint failed repeatedly in the recent past
+    pub fn is_cooling_down(&self) -> bool {
+        self.consecutive_failures >= COOL_DOWN_THRESHOLD
+            && self
+                .last_failure
+                .is_some_and(|failure| failure.elapsed() < COOL_DOWN_PERIOD)
+    }
+
+    fn record_success(&mut self, latency: Duration) {
+        self.latency = Some(match self.latency {
+            Some(average) => {
+                average.mul_f64(1.0 - SMOOTHING_FACTOR) + latency.mul_f64(SMOOTHING_FACTOR)
+            }
+            None => latency,
+        });
+        self.error_rate *= 1.0 - SMOOTHING_FACTOR;
+        self.successes += 1;
+        self.consecutive_failures = 0;
+    }
+
+    fn record_failure(&mut self) {
+        self.error_rate = self.error_rate * (1.0 - SMOOTHING_FACTOR) + SMOOTHING_FACTOR;
+        self.failures += 1;
+        self.consecutive_failures += 1;
+        self.last_failure = Some(Instant::now());
+    }
+}
+
+/// A set of node endpoints serving the same network, ranked by their observed health
+#[derive(Debug)]
+pub struct EndpointPool {
+    urls: Vec<String>,
+    health: RwLock<Vec<EndpointHealth>>,
+}
+
+impl EndpointPool {
+    pub fn new(urls: Vec<String>) -> Result<Self> {
+        ensure!(!urls.is_empty(), "At least one endpoint must be specified");
+        for url in urls.iter() {
+            ensure!(
+                url.starts_with("http://") || url.starts_with("https://"),
+                "specified url {url} invalid, the base url must start with or https:// (or http:// if doing local development)"
+            );
+        }
+        let health = RwLock::new(vec![EndpointHealth::default(); urls.len()]);
+        Ok(Self { urls, health })
+    }
+
+    /// Get the url of an endpoint
+    pub fn url(&self, index: usize) -> &str {
+        &self.urls[index]
+    }
+
+    /// Get the urls of all endpoints, in the order they were configured
+    pub fn urls(&self) -> &[String] {
+        &self.urls
+    }
+
+    /// Get the indices of the endpoints ordered from healthiest to least healthy. Endpoints with
+    /// equal health keep their configured order, so the first endpoint is preferred initially.
+    pub fn ranked(&self) -> Vec<usize> {
+        let health = self.health.read().unwrap();
+        let mut indices = (0..self.urls.len()).collect::<Vec<_>>();
+        indices.sort_by(|first, second| {
+            let (first, second) = (&health[*first], &health[*second]);
+            first
+                .is_cooling_down()
+                .cmp(&second.is_cooling_down())
+                .then(first.score().total_cmp(&second.score()))
+        });
+        indices
+    }
+
+    /// Get the url of the healthiest endpoint
+    pub fn best(&self) -> &str {
+        self.url(self.ranked()[0])
+    }
+
+    /// Get a snapshot of the health of every endpoint
+    pub fn health(&self) -> Vec<(String, EndpointHealth)> {
+        let health = self.health.read().unwrap();
+        self.urls
+            .iter()
+            .cloned()
+            .zip(health.iter().cloned())
+            .collect()
+    }
+
+    pub fn record_success(&self, index: usize, latency: Duration) {
+        self.health.write().unwrap()[index].record_success(latency);
+    }
+
+    pub fn record_failure(&self, index: usize) {
+        self.health.write().unwrap()[index].record_failure();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoints_are_ranked_by_health() {
+        let pool = EndpointPool::new(vec![
+            "http://first.node".to_string(),
+            "http://second.node".to_string(),
+            "http://third.node".to_string(),
+        ])
+        .unwrap();
+
+        // Ensure the configured order is kept until measurements exist
+        assert_eq!(pool.ranked(), vec![0, 1, 2]);
+        assert_eq!(pool.best(), "http://first.node");
+
+        // Ensure faster endpoints are preferred
+        pool.record_success(0, Duration::from_millis(300));
+        pool.record_success(1, Duration::from_millis(50));
+        pool.record_success(2, Duration::from_millis(100));
+        assert_eq!(pool.ranked(), vec![1, 2, 0]);
+
+        // Ensure failing endpoints are demoted, and put last once they fail repeatedly
+        for _ in 0..COOL_DOWN_THRESHOLD {
+            pool.record_failure(1);
+        }
+        assert_eq!(pool.ranked(), vec![2, 0, 1]);
+        assert!(pool.health()[1].1.is_cooling_down());
+
+        // Ensure endpoints recover after succeeding again
+        pool.record_success(1, Duration::from_millis(50));
+        assert!(!pool.health()[1].1.is_cooling_down());
+    }
+
+    #[test]
+    fn test_never_successful_endpoints_are_ranked_last() {
+        let pool = EndpointPool::new(vec![
+            "http://dead.node".to_string(),
+            "http://healthy.node".to_string(),
+        ])
+        .unwrap();
+
+        // A single failure does not start the cool down, but the endpoint never answered
+        pool.record_failure(0);
+        pool.record_success(1, Duration::from_millis(20));
+        assert!(!pool.health()[0].1.is_cooling_down());
+        assert_eq!(pool.ranked(), vec![1, 0]);
+        assert_eq!(pool.best(), "http://healthy.node");
+    }
+
+    #[test]
+    fn test_invalid_endpoints_are_rejected() {
+        assert!(EndpointPool::new(vec![]).is_err());
+        assert!(EndpointPool::new(vec!["ftp://first.node".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
+
+use app_dirs::AppDataType;
+use snarkvm::prelude::*;
+
+use crate::aleo_tools::APP_INFO;
+
+/// Cache of the programs fetched from the network.
+///
+/// Deployed programs cannot change, so once fetched a program is served from the cache instead
+/// of being requested again. Programs are keyed by the network they were fetched from and their
+/// ID. They are always kept in memory, and also stored on disk as source files if the cache has a
+/// directory, so they survive a restart.
+#[derive(Debug)]
+pub struct ProgramCache<N: Network> {
+    programs: RwLock<HashMap<(String, ProgramID<N>), Program<N>>>,
+    directory: Option<PathBuf>,
+}
+
+impl<N: Network> ProgramCache<N> {
+    /// Create a cache keeping programs in memory only
+    pub fn new() -> Self {
+        Self {
+            programs: Default::default(),
+            directory: None,
+        }
+    }
+
+    /// Create a cache keeping programs in memory and storing them in the given directory
+    pub fn with_directory(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            programs: Default::default(),
+            directory: Some(directory.into()),
+        }
+    }
+
+    /// Create a cache keeping programs in memory and storing them in the cache directory of the
+    /// application
+    pub fn in_app_cache() -> Result<Self> {
+        let directory = app_dirs::app_dir(AppDataType::UserCache, &APP_INFO, "programs")
+            .map_err(|error| anyhow!("Failed to open the program cache directory: {error}"))?;
+        Ok(Self::with_directory(directory))
+    }
+
+    /// Get the directory programs are stored in, if any
+    pub fn directory(&self) -> Option<&Path> {
+        self.directory.as_deref()
+    }
+
+    /// Get a program fetched from the given network, if it is cached. Programs found on disk
+    /// are kept in memory from then on.
+    pub fn get(&self, network_id: &str, program_id: &ProgramID<N>) -> Result<Option<Program<N>>> {
+        let key = (network_id.to_string(), *program_id);
+        if let Some(program) = self.programs.read().unwrap().get(&key) {
+            return Ok(Some(program.clone()));
+        }
+
+        let Some(path) = self.program_path(network_id, program_id) else {
+            return Ok(None);
+        };
+        if !path.exists() {
+            return Ok(None);
+        }
+        let program = Program::<N>::from_str(&fs::read_to_string(&path)?)?;
+        ensure!(
+            program.id() == program_id,
+            "The program cached at {} is not {program_id}",
+            path.display()
+        );
+        self.programs.write().unwrap().insert(key, program.clone());
+        Ok(Some(program))
+    }
+
+    /// Cache a program fetched from the given network
+    pub fn insert(&self, network_id: &str, program: &Program<N>) -> Result<()> {
+        if let Some(path) = self.program_path(network_id, program.id()) {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let temporary_path = path.with_extension("tmp");
+            fs::write(&temporary_path, program.to_string())?;
+            fs::rename(temporary_path, path)?;
+        }
+        self.programs
+            .write()
+            .unwrap()
+            .insert((network_id.to_string(), *program.id()), program.clone());
+        Ok(())
+    }
+
+    /// Remove a program fetched from the given network from the cache, e.g. after a local
+    /// development network was restarted
+    pub fn remove(&self, network_id: &str, program_id: &ProgramID<N>) -> Result<()> {
+        self.programs
+            .write()
+            .unwrap()
+            .remove(&(network_id.to_string(), *program_id));
+        if let Some(path) = self.program_path(network_id, program_id) {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn program_path(&self, network_id: &str, program_id: &ProgramID<N>) -> Option<PathBuf> {
+        let directory = self.directory.as_ref()?;
+        Some(directory.join(network_id).join(program_id.to_string()))
+    }
+}
+
+impl<N: Network> Default for ProgramCache<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aleo_tools::test_utils::HELLO_PROGRAM;
+
+    #[test]
+    fn test_programs_are_cached_per_network() {
+        let directory = tempfile::tempdir().unwrap();
+        let program = Program::<Testnet3>::from_str(HELLO_PROGRAM).unwrap();
+
+        let cache = ProgramCache::<Testnet3>::with_directory(directory.path());
+        assert_eq!(cache.get("testnet3", program.id()).unwrap(), None);
+        cache.insert("testnet3", &program).unwrap();
+        assert_eq!(
+            cache.get("testnet3", program.id()).unwrap(),
+            Some(program.clone())
+        );
+        assert_eq!(cache.get("devnet", program.id()).unwrap(), None);
+
+        // Ensure programs survive the cache being recreated
+        let cache = ProgramCache::<Testnet3>::with_directory(directory.path());
+        assert_eq!(
+            cache.get("testnet3", program.id()).unwrap(),
+            Some(program.clone())
+        );
+        cache.remove("testnet3", program.id()).unwrap();
+        assert_eq!(cache.get("testnet3", program.id()).unwrap(), None);
+    }
+}
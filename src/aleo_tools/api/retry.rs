@@ -0,0 +1,163 @@
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+/// Policy deciding how often and how quickly failed requests of the Aleo API client are retried.
+///
+/// Requests are retried when the transport fails to produce a response (e.g. a connection reset
+/// or timeout) or when the node answers with one of the `retryable_status_codes`. The delay
+/// between attempts grows exponentially from `initial_backoff` up to `max_backoff`.
+///
+/// `500 Internal Server Error` is not retried by default, since Aleo nodes also answer with it
+/// when an item does not exist, e.g. a program which is not deployed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts per request, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// Upper bound of the delay between two attempts
+    pub max_backoff: Duration,
+    /// Randomize delays to avoid many clients retrying in lockstep
+    pub jitter: bool,
+    /// Timeout of a single attempt
+    pub request_timeout: Option<Duration>,
+    /// Timeout of a request across all of its attempts
+    pub overall_timeout: Option<Duration>,
+    /// HTTP status codes which are considered transient
+    pub retryable_status_codes: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            request_timeout: Some(Duration::from_secs(30)),
+            overall_timeout: Some(Duration::from_secs(120)),
+            retryable_status_codes: vec![408, 429, 502, 503, 504],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy performing every request exactly once, without timeouts
+    pub fn no_retry() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+            jitter: false,
+            request_timeout: None,
+            overall_timeout: None,
+            retryable_status_codes: vec![],
+        }
+    }
+
+    /// Whether a response with the given status code should be retried
+    pub fn is_retryable_status(&self, status: u16) -> bool {
+        self.retryable_status_codes.contains(&status)
+    }
+
+    /// Delay to wait after the given (1-based) failed attempt
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .initial_backoff
+            .saturating_mul(1u32 << exponent)
+            .min(self.max_backoff);
+        if self.jitter && !delay.is_zero() {
+            // Equal jitter: wait at least half of the delay, and a random share of the rest
+            let half = delay / 2;
+            let extra = rand::thread_rng().gen_range(0..=half.as_millis() as u64);
+            half + Duration::from_millis(extra)
+        } else {
+            delay
+        }
+    }
+
+    /// Timeout for the next attempt of a request started at `started`, bounded by the time left
+    /// before the overall timeout expires
+    pub fn attempt_timeout(&self, started: Instant) -> Option<Duration> {
+        let remaining = self
+            .overall_timeout
+            .map(|timeout| timeout.saturating_sub(started.elapsed()));
+        match (self.request_timeout, remaining) {
+            (Some(timeout), Some(remaining)) => Some(timeout.min(remaining)),
+            (timeout, remaining) => timeout.or(remaining),
+        }
+    }
+
+    /// Decide whether another attempt may follow the given (1-based) failed attempt of a request
+    /// started at `started`. Returns the delay to wait before retrying.
+    pub fn next_retry(&self, attempt: u32, started: Instant) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        let delay = self.backoff(attempt);
+        match self.overall_timeout {
+            Some(timeout) if started.elapsed() + delay >= timeout => None,
+            _ => Some(delay),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_exponentially_up_to_the_limit() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(1000),
+            jitter: false,
+            ..Default::default()
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(4), Duration::from_millis(800));
+        assert_eq!(policy.backoff(5), Duration::from_millis(1000));
+        assert_eq!(policy.backoff(64), Duration::from_millis(1000));
+
+        // Ensure jittered delays stay between half and the full delay
+        let policy = RetryPolicy {
+            jitter: true,
+            ..policy
+        };
+        for _ in 0..100 {
+            let delay = policy.backoff(3);
+            assert!(delay >= Duration::from_millis(200) && delay <= Duration::from_millis(400));
+        }
+    }
+
+    #[test]
+    fn test_retry_limits() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            jitter: false,
+            initial_backoff: Duration::from_millis(10),
+            overall_timeout: None,
+            ..Default::default()
+        };
+        let started = Instant::now();
+        assert_eq!(policy.next_retry(1, started), Some(Duration::from_millis(10)));
+        assert_eq!(policy.next_retry(2, started), Some(Duration::from_millis(20)));
+        assert_eq!(policy.next_retry(3, started), None);
+        assert!(RetryPolicy::no_retry().next_retry(1, started).is_none());
+
+        // Ensure no retry is scheduled past the overall timeout
+        let policy = RetryPolicy {
+            overall_timeout: Some(Duration::from_millis(5)),
+            ..policy
+        };
+        assert_eq!(policy.next_retry(1, started), None);
+        assert!(policy.attempt_timeout(started).unwrap() <= Duration::from_millis(5));
+
+        assert!(policy.is_retryable_status(503));
+        assert!(!policy.is_retryable_status(404));
+        assert!(!policy.is_retryable_status(500));
+    }
+}
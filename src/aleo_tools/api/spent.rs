@@ -0,0 +1,59 @@
+use snarkvm::prelude::Network;
+
+use super::TransportResponse;
+
+// Error snarkVM reports when no transition consumed or produced an input or output ID. snarkOS
+// nodes answer it with a `500 Internal Server Error` whose body reads "Something went wrong: "
+// followed by the error.
+const TRANSITION_NOT_FOUND: &str = "Failed to find the transition ID";
+
+/// Whether a record has been consumed by a transition on the ledger
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SpentStatus<N: Network> {
+    /// The serial number of the record was consumed by the given transition
+    Spent(N::TransitionID),
+    /// The node reported no transition consuming the serial number of the record
+    Unspent,
+    /// The status could not be determined, e.g. because no node could be reached
+    Unknown(String),
+}
+
+impl<N: Network> SpentStatus<N> {
+    pub fn is_spent(&self) -> bool {
+        matches!(self, Self::Spent(_))
+    }
+
+    pub fn is_unspent(&self) -> bool {
+        matches!(self, Self::Unspent)
+    }
+
+    /// Whether a node answered a `find/transitionID` lookup by reporting that no transition
+    /// consumed or produced the ID, rather than failing to look it up
+    pub fn is_transition_not_found(response: &TransportResponse) -> bool {
+        match response.status {
+            404 => true,
+            500 => response.body.contains(TRANSITION_NOT_FOUND),
+            _ => false,
+        }
+    }
+
+    /// Get the status of a record from the answer of a node to the `find/transitionID` lookup of
+    /// its serial number. Failures other than the transition not being found leave the status
+    /// unknown rather than reporting the record unspent.
+    pub fn from_transition_lookup(response: TransportResponse) -> Self {
+        if Self::is_transition_not_found(&response) {
+            return Self::Unspent;
+        }
+        if !response.is_success() {
+            return Self::Unknown(format!(
+                "status code {}: {}",
+                response.status, response.body
+            ));
+        }
+        match response.into_json::<Option<N::TransitionID>>() {
+            Ok(Some(transition_id)) => Self::Spent(transition_id),
+            Ok(None) => Self::Unspent,
+            Err(error) => Self::Unknown(format!("Failed to parse transition ID: {error}")),
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::{fmt::Debug, time::Duration};
+
+use serde::de::DeserializeOwned;
+use snarkvm::prelude::{bail, Result};
+
+/// HTTP method of a request sent through a [`Transport`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+}
+
+/// A request issued by the Aleo API client
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransportRequest {
+    pub method: Method,
+    pub url: String,
+    /// JSON encoded request body
+    pub body: Option<String>,
+    /// Maximum time to wait for the response
+    pub timeout: Option<Duration>,
+}
+
+impl TransportRequest {
+    /// Create a GET request for the given url
+    pub fn get(url: &str) -> Self {
+        Self {
+            method: Method::Get,
+            url: url.to_string(),
+            body: None,
+            timeout: None,
+        }
+    }
+
+    /// Create a POST request for the given url with a JSON encoded body
+    pub fn post(url: &str, body: String) -> Self {
+        Self {
+            method: Method::Post,
+            url: url.to_string(),
+            body: Some(body),
+            timeout: None,
+        }
+    }
+
+    /// Set the maximum time to wait for the response
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+/// A response received through a [`Transport`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransportResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl TransportResponse {
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+        }
+    }
+
+    /// Whether the response carries a 2xx status code
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Deserialize the JSON response body
+    pub fn into_json<T: DeserializeOwned>(self) -> Result<T> {
+        Ok(serde_json::from_str(&self.body)?)
+    }
+
+    /// Get the raw response body
+    pub fn into_string(self) -> String {
+        self.body
+    }
+}
+
+/// Percent-encode a segment of a request path, e.g. a mapping key, so that spaces, braces, commas
+/// and slashes of values in Aleo syntax do not break the path
+pub fn encode_path_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// Decode a percent-encoded segment of a request path
+pub fn decode_path_segment(segment: &str) -> Result<String> {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let Some(hex) = segment.get(index + 1..index + 3) else {
+                bail!("Invalid percent-encoding in path segment {segment}");
+            };
+            decoded.push(u8::from_str_radix(hex, 16)?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    Ok(String::from_utf8(decoded)?)
+}
+
+/// The HTTP layer used by [`AleoAPIClient`](super::AleoAPIClient) to reach an Aleo node.
+///
+/// Implementations only return an error when no response could be obtained at all (e.g. the
+/// connection failed). Responses with non-success status codes are returned as
+/// [`TransportResponse`]s so the client can decide how to handle them.
+pub trait Transport: Debug + Send + Sync {
+    fn send(&self, request: &TransportRequest) -> Result<TransportResponse>;
+}
+
+/// Default transport backed by a [`ureq::Agent`]
+#[derive(Clone, Debug)]
+pub struct UreqTransport {
+    agent: ureq::Agent,
+}
+
+impl UreqTransport {
+    pub fn new() -> Self {
+        Self::from_agent(ureq::Agent::new())
+    }
+
+    /// Use a preconfigured agent, e.g. one with custom timeouts or a proxy
+    pub fn from_agent(agent: ureq::Agent) -> Self {
+        Self { agent }
+    }
+}
+
+impl Default for UreqTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transport for UreqTransport {
+    fn send(&self, request: &TransportRequest) -> Result<TransportResponse> {
+        let mut http_request = match request.method {
+            Method::Get => self.agent.get(&request.url),
+            Method::Post => self
+                .agent
+                .post(&request.url)
+                .set("Content-Type", "application/json"),
+        };
+        if let Some(timeout) = request.timeout {
+            http_request = http_request.timeout(timeout);
+        }
+        let result = match &request.body {
+            Some(body) => http_request.send_string(body),
+            None => http_request.call(),
+        };
+        match result {
+            Ok(response) => {
+                let status = response.status();
+                Ok(TransportResponse::new(status, response.into_string()?))
+            }
+            Err(ureq::Error::Status(status, response)) => {
+                Ok(TransportResponse::new(status, response.into_string()?))
+            }
+            Err(ureq::Error::Transport(error)) => bail!("{error}"),
+        }
+    }
+}
@@ -0,0 +1,445 @@
+use rand::seq::SliceRandom;
+use snarkvm::prelude::*;
+
+use crate::aleo_tools::program_manager::Credits;
+
+/// An item holding an amount of microcredits which can be spent, e.g. a credits record
+pub trait Coin {
+    /// Get the amount of microcredits held
+    fn value(&self) -> u64;
+}
+
+impl Coin for u64 {
+    fn value(&self) -> u64 {
+        *self
+    }
+}
+
+impl<N: Network> Coin for Record<N, Plaintext<N>> {
+    fn value(&self) -> u64 {
+        self.microcredits().unwrap_or(0)
+    }
+}
+
+impl<N: Network> Coin for (Field<N>, Record<N, Plaintext<N>>) {
+    fn value(&self) -> u64 {
+        self.1.value()
+    }
+}
+
+/// Strategy used to pick the records paying for a transfer and its fee
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SelectionStrategy {
+    /// Spend the largest records, consolidating funds over time
+    LargestFirst,
+    /// Spend the smallest records which cover the amount and the fee
+    #[default]
+    SmallestSufficient,
+    /// Spend the pair of records leaving the least change
+    MinimizeChange,
+    /// Spend random sufficient records, so the records spent reveal less about the wallet
+    Random,
+}
+
+/// Records selected to pay for a transfer and its fee.
+///
+/// Transfers spend a single record for the amount and a single record for the fee. When no
+/// single record is large enough, several records are selected for that part, and they must be
+/// joined with `credits.aleo/join` before the transfer can be made.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoinSelection<T> {
+    /// Records paying for the amount, empty if the amount is zero
+    pub amount_records: Vec<T>,
+    /// Records paying for the fee, empty if the fee is zero
+    pub fee_records: Vec<T>,
+}
+
+impl<T: Coin> CoinSelection<T> {
+    /// Whether records must be joined before the transfer can be made
+    pub fn is_join_required(&self) -> bool {
+        self.amount_records.len() > 1 || self.fee_records.len() > 1
+    }
+
+    /// Number of joins needed before the transfer can be made
+    pub fn joins(&self) -> usize {
+        self.amount_records.len().saturating_sub(1) + self.fee_records.len().saturating_sub(1)
+    }
+
+    /// Get the record paying for the amount, if no join is needed for it
+    pub fn amount_record(&self) -> Option<&T> {
+        match self.amount_records.as_slice() {
+            [record] => Some(record),
+            _ => None,
+        }
+    }
+
+    /// Get the record paying for the fee, if no join is needed for it
+    pub fn fee_record(&self) -> Option<&T> {
+        match self.fee_records.as_slice() {
+            [record] => Some(record),
+            _ => None,
+        }
+    }
+
+    /// Total microcredits of the selected records
+    pub fn total(&self) -> u64 {
+        self.amount_records
+            .iter()
+            .chain(self.fee_records.iter())
+            .map(Coin::value)
+            .sum()
+    }
+}
+
+/// Select records from the given set to pay for a transfer of `amount` microcredits and a fee
+/// of `fee` microcredits, using the given strategy.
+///
+/// If no single records cover the amount and the fee, the records to join are selected largest
+/// first, to need as few joins as possible, falling back to a search of every way of sharing the
+/// records between the amount and the fee. Fails if the records do not hold enough credits, or if
+/// a record must be split to pay for both.
+pub fn select_coins<T: Coin + Clone>(
+    records: &[T],
+    amount: u64,
+    fee: u64,
+    strategy: SelectionStrategy,
+) -> Result<CoinSelection<T>> {
+    let available = records
+        .iter()
+        .map(|record| record.value() as u128)
+        .sum::<u128>();
+    let required = amount as u128 + fee as u128;
+    ensure!(
+        available >= required,
+        "Insufficient funds: {available} microcredits available, {required} microcredits required"
+    );
+
+    let values = records.iter().map(Coin::value).collect::<Vec<_>>();
+    let pick = |indices: &[usize]| {
+        indices
+            .iter()
+            .map(|index| records[*index].clone())
+            .collect()
+    };
+    let (amount_indices, fee_indices) = match select_pair(&values, amount, fee, strategy) {
+        Some(pair) => pair,
+        None => select_joins(&values, amount, fee)?,
+    };
+    Ok(CoinSelection {
+        amount_records: pick(&amount_indices),
+        fee_records: pick(&fee_indices),
+    })
+}
+
+// Select a single record for the amount and a different single record for the fee, skipping the
+// parts which are zero.
+fn select_pair(
+    values: &[u64],
+    amount: u64,
+    fee: u64,
+    strategy: SelectionStrategy,
+) -> Option<(Vec<usize>, Vec<usize>)> {
+    let mut order = (0..values.len()).collect::<Vec<_>>();
+    match strategy {
+        SelectionStrategy::LargestFirst => {
+            order.sort_by_key(|index| std::cmp::Reverse(values[*index]))
+        }
+        SelectionStrategy::SmallestSufficient => order.sort_by_key(|index| values[*index]),
+        SelectionStrategy::Random => order.shuffle(&mut rand::thread_rng()),
+        SelectionStrategy::MinimizeChange => {
+            return select_pair_minimizing_change(values, amount, fee)
+        }
+    }
+
+    let first_covering = |target: u64, excluded: Option<usize>| {
+        order
+            .iter()
+            .copied()
+            .find(|index| Some(*index) != excluded && values[*index] >= target)
+    };
+    let part = |target: u64, excluded: Option<usize>| match target {
+        0 => Some(None),
+        _ => first_covering(target, excluded).map(Some),
+    };
+
+    // Try covering the amount first, then the fee first, since the record preferred for one part
+    // may be the only one covering the other
+    let amount_first =
+        part(amount, None).and_then(|amount_index| Some((amount_index, part(fee, amount_index)?)));
+    let fee_first =
+        || part(fee, None).and_then(|fee_index| Some((part(amount, fee_index)?, fee_index)));
+    let (amount_index, fee_index) = amount_first.or_else(fee_first)?;
+    Some((
+        amount_index.into_iter().collect(),
+        fee_index.into_iter().collect(),
+    ))
+}
+
+fn select_pair_minimizing_change(
+    values: &[u64],
+    amount: u64,
+    fee: u64,
+) -> Option<(Vec<usize>, Vec<usize>)> {
+    let candidates = |target: u64| -> Vec<Option<usize>> {
+        match target {
+            0 => vec![None],
+            _ => (0..values.len())
+                .filter(|index| values[*index] >= target)
+                .map(Some)
+                .collect(),
+        }
+    };
+    let value = |index: Option<usize>| index.map_or(0, |index| values[index] as u128);
+
+    let mut best: Option<(u128, Option<usize>, Option<usize>)> = None;
+    for amount_index in candidates(amount) {
+        for fee_index in candidates(fee) {
+            if amount_index.is_some() && amount_index == fee_index {
+                continue;
+            }
+            let total = value(amount_index) + value(fee_index);
+            if !best.is_some_and(|(best_total, ..)| best_total <= total) {
+                best = Some((total, amount_index, fee_index));
+            }
+        }
+    }
+    let (_, amount_index, fee_index) = best?;
+    Some((
+        amount_index.into_iter().collect(),
+        fee_index.into_iter().collect(),
+    ))
+}
+
+// Select the records to join when no pair of single records covers the transfer. The largest
+// records are joined, paying for the fee first with the smallest record covering it if there is
+// one, then for the amount first. When both fills fail, every way of sharing the records between
+// the amount and the fee is searched.
+fn select_joins(values: &[u64], amount: u64, fee: u64) -> Result<(Vec<usize>, Vec<usize>)> {
+    let mut largest_first = (0..values.len()).collect::<Vec<_>>();
+    largest_first.sort_by_key(|index| std::cmp::Reverse(values[*index]));
+
+    let fill = |first: u64, second: u64| {
+        let mut used = vec![false; values.len()];
+        let first_indices = match largest_first
+            .iter()
+            .rev()
+            .find(|index| first > 0 && values[**index] >= first)
+        {
+            Some(index) => {
+                used[*index] = true;
+                vec![*index]
+            }
+            None => take_largest(values, &largest_first, first, &mut used)?,
+        };
+        let second_indices = take_largest(values, &largest_first, second, &mut used)?;
+        Some((first_indices, second_indices))
+    };
+    let selection = fill(fee, amount)
+        .map(|(fee_indices, amount_indices)| (amount_indices, fee_indices))
+        .or_else(|| fill(amount, fee))
+        .or_else(|| search_joins(values, &largest_first, amount, fee));
+    if let Some(selection) = selection {
+        return Ok(selection);
+    }
+
+    // Joining records never helps here, since joins only make fewer totals reachable
+    let covering = values
+        .iter()
+        .find(|value| **value as u128 >= amount as u128 + fee as u128);
+    match covering {
+        Some(value) => bail!(
+            "The records cannot cover both the amount and the fee, consider splitting the record of {value} microcredits with credits.aleo/split first"
+        ),
+        None => bail!(
+            "The records cannot cover both the amount and the fee, consider splitting a record with credits.aleo/split first"
+        ),
+    }
+}
+
+// Take the largest records which are not used yet until they cover the target
+fn take_largest(
+    values: &[u64],
+    largest_first: &[usize],
+    target: u64,
+    used: &mut [bool],
+) -> Option<Vec<usize>> {
+    let mut selected = vec![];
+    let mut total = 0u128;
+    for index in largest_first.iter().copied() {
+        if total >= target as u128 {
+            break;
+        }
+        if !used[index] {
+            used[index] = true;
+            total += values[index] as u128;
+            selected.push(index);
+        }
+    }
+    (total >= target as u128).then_some(selected)
+}
+
+// Search for records covering the amount whose remaining records still cover the fee, i.e. records
+// whose total lies between the amount and the total of every record minus the fee. The fee is
+// then paid by the largest remaining records.
+fn search_joins(
+    values: &[u64],
+    largest_first: &[usize],
+    amount: u64,
+    fee: u64,
+) -> Option<(Vec<usize>, Vec<usize>)> {
+    // Totals of the records from every position of the search to the end
+    let mut remaining = vec![0u128; largest_first.len() + 1];
+    for position in (0..largest_first.len()).rev() {
+        remaining[position] = remaining[position + 1] + values[largest_first[position]] as u128;
+    }
+    let bounds = (amount as u128, remaining[0].checked_sub(fee as u128)?);
+
+    let mut amount_indices = vec![];
+    if !search_subset(
+        values,
+        largest_first,
+        &remaining,
+        bounds,
+        0,
+        0,
+        &mut amount_indices,
+    ) {
+        return None;
+    }
+    let mut used = vec![false; values.len()];
+    for index in &amount_indices {
+        used[*index] = true;
+    }
+    let fee_indices = take_largest(values, largest_first, fee, &mut used)?;
+    Some((amount_indices, fee_indices))
+}
+
+// Depth-first search for records, taken in the given order from the given position on, bringing
+// the total of the selected records within the bounds
+fn search_subset(
+    values: &[u64],
+    order: &[usize],
+    remaining: &[u128],
+    bounds: (u128, u128),
+    position: usize,
+    total: u128,
+    selected: &mut Vec<usize>,
+) -> bool {
+    if total >= bounds.0 {
+        return true;
+    }
+    if position == order.len() || total + remaining[position] < bounds.0 {
+        return false;
+    }
+    let index = order[position];
+    let with_record = total + values[index] as u128;
+    if with_record <= bounds.1 {
+        selected.push(index);
+        if search_subset(
+            values,
+            order,
+            remaining,
+            bounds,
+            position + 1,
+            with_record,
+            selected,
+        ) {
+            return true;
+        }
+        selected.pop();
+    }
+    search_subset(
+        values,
+        order,
+        remaining,
+        bounds,
+        position + 1,
+        total,
+        selected,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aleo_tools::test_utils::{RECORD_2000000001_MICROCREDITS, RECORD_5_MICROCREDITS};
+
+    const RECORDS: [u64; 5] = [40, 10, 25, 5, 100];
+
+    #[test]
+    fn test_single_records_are_selected_by_strategy() {
+        let select = |strategy| select_coins(&RECORDS, 20, 8, strategy).unwrap();
+
+        let selection = select(SelectionStrategy::LargestFirst);
+        assert_eq!(selection.amount_record(), Some(&100));
+        assert_eq!(selection.fee_record(), Some(&40));
+        assert!(!selection.is_join_required());
+
+        let selection = select(SelectionStrategy::SmallestSufficient);
+        assert_eq!(selection.amount_record(), Some(&25));
+        assert_eq!(selection.fee_record(), Some(&10));
+
+        let selection = select(SelectionStrategy::MinimizeChange);
+        assert_eq!(selection.total(), 35);
+
+        for _ in 0..20 {
+            let selection = select(SelectionStrategy::Random);
+            let (amount_record, fee_record) = (
+                selection.amount_record().unwrap(),
+                selection.fee_record().unwrap(),
+            );
+            assert!(*amount_record >= 20 && *fee_record >= 8);
+            assert_ne!(amount_record, fee_record);
+        }
+
+        // Ensure zero fees do not select a fee record
+        let selection = select_coins(&RECORDS, 20, 0, SelectionStrategy::default()).unwrap();
+        assert_eq!(selection.amount_record(), Some(&25));
+        assert!(selection.fee_records.is_empty());
+    }
+
+    #[test]
+    fn test_records_are_not_spent_twice() {
+        // The largest record covers both parts, so the other part must use the remaining record
+        let selection =
+            select_coins(&[20u64, 30], 10, 25, SelectionStrategy::LargestFirst).unwrap();
+        assert_eq!(selection.amount_record(), Some(&20));
+        assert_eq!(selection.fee_record(), Some(&30));
+
+        // A single record covering both parts cannot pay for both
+        let error = select_coins(&[40u64], 10, 25, SelectionStrategy::default()).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("splitting the record of 40 microcredits"));
+
+        // Ensure records which cannot be joined largest first are shared between both parts
+        let selection =
+            select_coins(&[6u64, 5, 5, 4], 10, 10, SelectionStrategy::default()).unwrap();
+        assert_eq!(selection.amount_records, vec![6, 4]);
+        assert_eq!(selection.fee_records, vec![5, 5]);
+        assert_eq!(selection.joins(), 2);
+    }
+
+    #[test]
+    fn test_joins_are_reported() {
+        let selection = select_coins(&RECORDS, 150, 8, SelectionStrategy::default()).unwrap();
+        assert!(selection.is_join_required());
+        assert_eq!(selection.fee_records, vec![10]);
+        assert_eq!(selection.amount_records, vec![100, 40, 25]);
+        assert_eq!(selection.joins(), 2);
+        assert!(selection.amount_record().is_none());
+
+        // Ensure insufficient funds are reported
+        assert!(select_coins(&RECORDS, 180, 1, SelectionStrategy::default()).is_err());
+        assert!(select_coins::<u64>(&[], 1, 0, SelectionStrategy::default()).is_err());
+    }
+
+    #[test]
+    fn test_credits_records_are_selected() {
+        let records = [RECORD_5_MICROCREDITS, RECORD_2000000001_MICROCREDITS]
+            .map(|record| Record::<Testnet3, Plaintext<Testnet3>>::from_str(record).unwrap());
+        let selection = select_coins(&records, 1_000_000, 5, SelectionStrategy::default()).unwrap();
+        assert_eq!(selection.amount_record(), Some(&records[1]));
+        assert_eq!(selection.fee_record(), Some(&records[0]));
+    }
+}
@@ -0,0 +1,426 @@
+use std::collections::HashSet;
+
+use snarkvm::{circuit::prelude::IndexMap, prelude::*};
+
+use crate::aleo_tools::import_graph::ImportGraph;
+
+// Sizes in bytes of the components of serialized transactions
+const VERSION_SIZE: u64 = 1;
+const FIELD_SIZE: u64 = 32;
+const CERTIFICATE_SIZE: u64 = 50;
+// A transition ends with its public key, commitment and signer commitment
+const TRANSITION_KEYS_SIZE: u64 = 3 * FIELD_SIZE;
+// Varuna batches the circuits of an execution into one proof, whose size grows with the number of
+// circuits and of instances of each circuit
+const PROOF_BASE_SIZE: u64 = 550;
+const PROOF_SIZE_PER_CIRCUIT: u64 = 488;
+const PROOF_SIZE_PER_INSTANCE: u64 = 144;
+// Number of bits of data packed in a field element of a ciphertext
+const FIELD_DATA_BITS: u64 = 252;
+
+/// Fee estimator computing the cost of executions and deployments from the instructions and
+/// types of a program, without a network client and without proving.
+///
+/// The estimator is built from a program and its imports, resolved beforehand e.g. with
+/// [`ImportGraph`]. Storage costs are computed from the sizes of the transitions, proofs and keys
+/// the transaction would contain, and finalize costs from the finalize logic of the functions
+/// executed. Values whose size is only known at runtime, such as strings, are counted as empty.
+///
+/// Disclaimer: Fee estimation is experimental and may not represent a correct estimate on any current or future network
+#[derive(Clone, Debug)]
+pub struct FeeEstimator<N: Network> {
+    program_id: ProgramID<N>,
+    programs: IndexMap<ProgramID<N>, Program<N>>,
+}
+
+impl<N: Network> FeeEstimator<N> {
+    /// Create an estimator for a program, given every program it imports directly or indirectly.
+    /// `credits.aleo` does not need to be given.
+    pub fn new(
+        program: &Program<N>,
+        imports: impl IntoIterator<Item = Program<N>>,
+    ) -> Result<Self> {
+        let mut imports = imports
+            .into_iter()
+            .map(|import| (*import.id(), import))
+            .collect::<IndexMap<_, _>>();
+        let credits = Program::<N>::credits()?;
+        imports.entry(*credits.id()).or_insert(credits);
+
+        let graph = ImportGraph::build(program, |import_id| {
+            imports
+                .get(import_id)
+                .cloned()
+                .ok_or_else(|| anyhow!("Import {import_id} was not provided to the fee estimator"))
+        })?;
+        Ok(Self {
+            program_id: *program.id(),
+            programs: graph
+                .topological_order()
+                .map(|program| (*program.id(), program.clone()))
+                .collect(),
+        })
+    }
+
+    /// Estimate the cost of executing a function of the program in microcredits. The response is
+    /// in the form of (total_cost, (storage_cost, finalize_cost)), like
+    /// [`execution_cost`].
+    pub fn estimate_execution_fee(
+        &self,
+        function: impl TryInto<Identifier<N>>,
+    ) -> Result<(u64, (u64, u64))> {
+        let function_name = function
+            .try_into()
+            .map_err(|_| anyhow!("Invalid function name"))?;
+        let mut transitions = vec![];
+        self.collect_transitions(&self.program_id, &function_name, &mut transitions)?;
+
+        let mut storage_cost = VERSION_SIZE + 1 + FIELD_SIZE + 1;
+        let mut finalize_cost = 0u64;
+        let mut circuits = HashSet::new();
+        let mut has_record_inputs = false;
+        for (program_id, function_name) in &transitions {
+            let program = self.program(program_id)?;
+            let function = program.get_function(function_name)?;
+            storage_cost += self.transition_size(program, &function)?;
+            if let Some(finalize) = function.finalize_logic() {
+                finalize_cost = finalize_cost
+                    .checked_add(cost_in_microcredits(finalize)?)
+                    .ok_or_else(|| anyhow!("The finalize cost computation overflowed"))?;
+            }
+            circuits.insert((*program_id, *function_name));
+            has_record_inputs |= function.inputs().iter().any(|input| {
+                matches!(
+                    input.value_type(),
+                    ValueType::Record(..) | ValueType::ExternalRecord(..)
+                )
+            });
+        }
+
+        // Executions consuming records also prove the inclusion of the records
+        let num_circuits = circuits.len() as u64 + u64::from(has_record_inputs);
+        let extra_instances = (transitions.len() - circuits.len()) as u64;
+        storage_cost += PROOF_BASE_SIZE
+            + num_circuits * PROOF_SIZE_PER_CIRCUIT
+            + extra_instances * PROOF_SIZE_PER_INSTANCE;
+
+        Ok((storage_cost + finalize_cost, (storage_cost, finalize_cost)))
+    }
+
+    /// Estimate the cost of deploying the program in microcredits. The response is in the form of
+    /// (total_cost, (storage_cost, namespace_cost)), like [`deployment_cost`].
+    pub fn estimate_deployment_fee(&self) -> Result<(u64, (u64, u64))> {
+        let program = self.program(&self.program_id)?;
+
+        // Verifying keys have the same size for every circuit
+        let verifying_key_size = VERSION_SIZE
+            + N::get_credits_verifying_key("transfer_public".to_string())?
+                .to_bytes_le()?
+                .len() as u64;
+        let functions_size = program
+            .functions()
+            .keys()
+            .map(|function_name| {
+                Self::identifier_size(function_name) + verifying_key_size + CERTIFICATE_SIZE
+            })
+            .sum::<u64>();
+        let size = VERSION_SIZE + 2 + program.to_bytes_le()?.len() as u64 + 2 + functions_size;
+        let storage_cost = size
+            .checked_mul(N::DEPLOYMENT_FEE_MULTIPLIER)
+            .ok_or_else(|| anyhow!("The storage cost computation overflowed for a deployment"))?;
+
+        let num_characters = program.id().name().to_string().chars().count() as u32;
+        let namespace_cost = 10u64
+            .checked_pow(10u32.saturating_sub(num_characters))
+            .ok_or_else(|| anyhow!("The namespace cost computation overflowed for a deployment"))?
+            .saturating_mul(1_000_000);
+
+        Ok((
+            storage_cost + namespace_cost,
+            (storage_cost, namespace_cost),
+        ))
+    }
+
+    fn program(&self, program_id: &ProgramID<N>) -> Result<&Program<N>> {
+        self.programs
+            .get(program_id)
+            .ok_or_else(|| anyhow!("Program {program_id} is not known to the fee estimator"))
+    }
+
+    // Collect the transitions of an execution of a function, the transitions of the external
+    // functions it calls first, as they are ordered in the execution
+    fn collect_transitions(
+        &self,
+        program_id: &ProgramID<N>,
+        function_name: &Identifier<N>,
+        transitions: &mut Vec<(ProgramID<N>, Identifier<N>)>,
+    ) -> Result<()> {
+        let function = self.program(program_id)?.get_function(function_name)?;
+        for instruction in function.instructions() {
+            if let Instruction::Call(call) = instruction {
+                if let CallOperator::Locator(locator) = call.operator() {
+                    let program = self.program(locator.program_id())?;
+                    if program.contains_function(locator.resource()) {
+                        self.collect_transitions(
+                            locator.program_id(),
+                            locator.resource(),
+                            transitions,
+                        )?;
+                    }
+                }
+            }
+        }
+        transitions.push((*program_id, *function_name));
+        Ok(())
+    }
+
+    fn transition_size(&self, program: &Program<N>, function: &Function<N>) -> Result<u64> {
+        let mut size = VERSION_SIZE
+            + FIELD_SIZE
+            + Self::program_id_size(program.id())
+            + Self::identifier_size(function.name())
+            + 1
+            + 1
+            + TRANSITION_KEYS_SIZE;
+        for input in function.inputs() {
+            size += self.value_size(program, input.value_type(), false)?;
+        }
+        for output in function.outputs() {
+            size += self.value_size(program, output.value_type(), true)?;
+        }
+        Ok(size)
+    }
+
+    // Size of an input or output of a transition, with its variant and ID
+    fn value_size(
+        &self,
+        program: &Program<N>,
+        value_type: &ValueType<N>,
+        is_output: bool,
+    ) -> Result<u64> {
+        let size = match value_type {
+            ValueType::Constant(plaintext_type) | ValueType::Public(plaintext_type) => {
+                FIELD_SIZE + 1 + self.plaintext_size(program, plaintext_type)?
+            }
+            ValueType::Private(plaintext_type) => {
+                let plaintext_size = self.plaintext_size(program, plaintext_type)?;
+                FIELD_SIZE + 1 + Self::ciphertext_size(plaintext_size)
+            }
+            // Outputs carry the commitment, checksum and ciphertext of the record, inputs its
+            // serial number and tag
+            ValueType::Record(record_name) if is_output => {
+                2 * FIELD_SIZE + 1 + self.record_size(program, record_name)?
+            }
+            ValueType::Record(..) => 2 * FIELD_SIZE,
+            ValueType::ExternalRecord(..) => FIELD_SIZE,
+            ValueType::Future(locator) => FIELD_SIZE + 1 + self.future_size(locator)?,
+        };
+        Ok(1 + size)
+    }
+
+    fn plaintext_size(
+        &self,
+        program: &Program<N>,
+        plaintext_type: &PlaintextType<N>,
+    ) -> Result<u64> {
+        let size = match plaintext_type {
+            PlaintextType::Literal(literal_type) => 2 + Self::literal_size(literal_type),
+            PlaintextType::Struct(struct_name) => {
+                let mut size = 1;
+                for (member_name, member_type) in program.get_struct(struct_name)?.members() {
+                    size += Self::identifier_size(member_name)
+                        + 2
+                        + self.plaintext_size(program, member_type)?;
+                }
+                size
+            }
+            PlaintextType::Array(array_type) => {
+                let element_size =
+                    2 + self.plaintext_size(program, array_type.next_element_type())?;
+                4 + u64::from(**array_type.length()) * element_size
+            }
+        };
+        Ok(1 + size)
+    }
+
+    fn record_size(&self, program: &Program<N>, record_name: &Identifier<N>) -> Result<u64> {
+        let record_type = program.get_record(record_name)?;
+        let owner_size = match record_type.owner().is_public() {
+            true => FIELD_SIZE,
+            false => Self::ciphertext_size(FIELD_SIZE),
+        };
+        let mut size = 1 + owner_size + 1 + FIELD_SIZE;
+        for (entry_name, entry_type) in record_type.entries() {
+            let entry_size = match entry_type {
+                EntryType::Constant(plaintext_type) | EntryType::Public(plaintext_type) => {
+                    self.plaintext_size(program, plaintext_type)?
+                }
+                EntryType::Private(plaintext_type) => {
+                    Self::ciphertext_size(self.plaintext_size(program, plaintext_type)?)
+                }
+            };
+            size += Self::identifier_size(entry_name) + 2 + 1 + entry_size;
+        }
+        Ok(size)
+    }
+
+    // Size of the future of a call to a finalize block, whose arguments are the finalize inputs
+    fn future_size(&self, locator: &Locator<N>) -> Result<u64> {
+        let program = self.program(locator.program_id())?;
+        let function = program.get_function(locator.resource())?;
+        let mut size = Self::program_id_size(locator.program_id())
+            + Self::identifier_size(locator.resource())
+            + 1;
+        if let Some(finalize) = function.finalize_logic() {
+            for input in finalize.inputs() {
+                size += 1
+                    + 2
+                    + match input.finalize_type() {
+                        FinalizeType::Plaintext(plaintext_type) => {
+                            self.plaintext_size(program, plaintext_type)?
+                        }
+                        FinalizeType::Future(locator) => self.future_size(locator)?,
+                    };
+            }
+        }
+        Ok(size)
+    }
+
+    fn literal_size(literal_type: &LiteralType) -> u64 {
+        match literal_type {
+            LiteralType::Boolean | LiteralType::I8 | LiteralType::U8 => 1,
+            LiteralType::I16 | LiteralType::U16 => 2,
+            LiteralType::I32 | LiteralType::U32 => 4,
+            LiteralType::I64 | LiteralType::U64 => 8,
+            LiteralType::I128 | LiteralType::U128 => 16,
+            LiteralType::Address
+            | LiteralType::Field
+            | LiteralType::Group
+            | LiteralType::Scalar => FIELD_SIZE,
+            LiteralType::Signature => 4 * FIELD_SIZE,
+            // The length of the string is known at runtime only
+            LiteralType::String => 2,
+        }
+    }
+
+    fn ciphertext_size(plaintext_size: u64) -> u64 {
+        2 + (plaintext_size * 8).div_ceil(FIELD_DATA_BITS) * FIELD_SIZE
+    }
+
+    fn identifier_size(identifier: &Identifier<N>) -> u64 {
+        1 + identifier.to_string().len() as u64
+    }
+
+    fn program_id_size(program_id: &ProgramID<N>) -> u64 {
+        Self::identifier_size(program_id.name()) + Self::identifier_size(program_id.network())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aleo_tools::test_utils::{
+        FEE_ESTIMATION_PROGRAM, HELLO_PROGRAM, MULTIPLY_IMPORT_PROGRAM, MULTIPLY_PROGRAM,
+    };
+    use crate::models::constants::TESTNET_PRIVATE_KEY;
+    use snarkvm::{
+        circuit::AleoV0,
+        ledger::{
+            query::Query,
+            store::{helpers::memory::ConsensusMemory, ConsensusStore},
+        },
+    };
+
+    // Estimates must be within this fraction of the cost of the actual transaction
+    const TOLERANCE: f64 = 0.1;
+
+    fn assert_within_tolerance(estimate: u64, actual: u64) {
+        let difference = (estimate as f64 - actual as f64).abs() / actual as f64;
+        assert!(
+            difference <= TOLERANCE,
+            "Estimate {estimate} differs from {actual} by more than {TOLERANCE}"
+        );
+    }
+
+    fn actual_execution_cost(
+        programs: &[&Program<Testnet3>],
+        function: &str,
+        inputs: &[&str],
+    ) -> (u64, (u64, u64)) {
+        let rng = &mut rand::thread_rng();
+        let private_key = PrivateKey::<Testnet3>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+        let store = ConsensusStore::<Testnet3, ConsensusMemory<Testnet3>>::open(None).unwrap();
+        let vm = VM::from(store).unwrap();
+        for program in programs {
+            vm.process().write().add_program(program).unwrap();
+        }
+
+        let program_id = programs.last().unwrap().id();
+        let authorization = vm
+            .authorize(
+                &private_key,
+                program_id,
+                function,
+                inputs.iter().copied(),
+                rng,
+            )
+            .unwrap();
+        let locator = Locator::new(*program_id, Identifier::from_str(function).unwrap());
+        let (_, mut trace) = vm
+            .process()
+            .write()
+            .execute::<AleoV0, _>(authorization, rng)
+            .unwrap();
+        trace
+            .prepare(Query::from(vm.block_store().clone()))
+            .unwrap();
+        let execution = trace
+            .prove_execution::<AleoV0, _>(&locator.to_string(), rng)
+            .unwrap();
+        execution_cost(&vm, &execution).unwrap()
+    }
+
+    #[test]
+    fn test_execution_fees_match_execution_cost() {
+        let hello = Program::<Testnet3>::from_str(HELLO_PROGRAM).unwrap();
+        let estimator = FeeEstimator::new(&hello, []).unwrap();
+        let (estimate, _) = estimator.estimate_execution_fee("hello").unwrap();
+        let (actual, _) = actual_execution_cost(&[&hello], "hello", &["5u32", "6u32"]);
+        assert_within_tolerance(estimate, actual);
+
+        // Ensure the transitions of imported functions are counted
+        let multiply = Program::<Testnet3>::from_str(MULTIPLY_PROGRAM).unwrap();
+        let double = Program::<Testnet3>::from_str(MULTIPLY_IMPORT_PROGRAM).unwrap();
+        assert!(FeeEstimator::new(&double, []).is_err());
+        let estimator = FeeEstimator::new(&double, [multiply.clone()]).unwrap();
+        let (estimate, _) = estimator.estimate_execution_fee("double_it").unwrap();
+        let (actual, _) = actual_execution_cost(&[&multiply, &double], "double_it", &["5u32"]);
+        assert_within_tolerance(estimate, actual);
+
+        // Ensure finalize costs are computed exactly
+        let program = Program::<Testnet3>::from_str(FEE_ESTIMATION_PROGRAM).unwrap();
+        let estimator = FeeEstimator::new(&program, []).unwrap();
+        let (_, (_, finalize_cost)) = estimator.estimate_execution_fee("add_size_record").unwrap();
+        let (_, (_, actual_finalize_cost)) = actual_execution_cost(
+            &[&program],
+            "add_size_record",
+            &["1field", "2field", "3u64"],
+        );
+        assert_eq!(finalize_cost, actual_finalize_cost);
+    }
+
+    #[test]
+    fn test_deployment_fees_match_deployment_cost() {
+        let program = Program::<Testnet3>::from_str(FEE_ESTIMATION_PROGRAM).unwrap();
+        let estimator = FeeEstimator::new(&program, []).unwrap();
+        let (estimate, (_, namespace_cost)) = estimator.estimate_deployment_fee().unwrap();
+
+        let process = Process::<Testnet3>::load().unwrap();
+        let deployment = process
+            .deploy::<AleoV0, _>(&program, &mut rand::thread_rng())
+            .unwrap();
+        let (actual, (_, actual_namespace_cost)) = deployment_cost(&deployment).unwrap();
+        assert_eq!(namespace_cost, actual_namespace_cost);
+        assert_within_tolerance(estimate, actual);
+    }
+}
@@ -0,0 +1,192 @@
+use snarkvm::{circuit::prelude::IndexMap, prelude::*};
+
+/// The graph of the imports of a program, including the imports of its imports.
+///
+/// Every program of the graph is resolved once, even if several programs import it, and the
+/// graph is guaranteed to be acyclic: building it fails with the import path of the first cycle
+/// found.
+#[derive(Clone, Debug)]
+pub struct ImportGraph<N: Network> {
+    root: ProgramID<N>,
+    /// The programs of the graph in topological order, imports before the programs importing them
+    programs: IndexMap<ProgramID<N>, Program<N>>,
+}
+
+impl<N: Network> ImportGraph<N> {
+    /// Build the import graph of a program, resolving every imported program with the given
+    /// function, e.g. by fetching it from the network
+    pub fn build(
+        program: &Program<N>,
+        mut resolve: impl FnMut(&ProgramID<N>) -> Result<Program<N>>,
+    ) -> Result<Self> {
+        let mut programs = IndexMap::new();
+        let mut path = vec![*program.id()];
+        Self::visit(program.clone(), &mut path, &mut programs, &mut resolve)?;
+        Ok(Self {
+            root: *program.id(),
+            programs,
+        })
+    }
+
+    // Visit the imports of a program depth first, adding every program after its imports. The
+    // path holds the programs being visited, from the root to the current program.
+    fn visit(
+        program: Program<N>,
+        path: &mut Vec<ProgramID<N>>,
+        programs: &mut IndexMap<ProgramID<N>, Program<N>>,
+        resolve: &mut impl FnMut(&ProgramID<N>) -> Result<Program<N>>,
+    ) -> Result<()> {
+        for import_id in program.imports().keys() {
+            if let Some(start) = path.iter().position(|id| id == import_id) {
+                let cycle = path[start..]
+                    .iter()
+                    .chain(std::iter::once(import_id))
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>();
+                bail!(
+                    "Circular dependency discovered in program imports: {}",
+                    cycle.join(" -> ")
+                );
+            }
+            if programs.contains_key(import_id) {
+                continue;
+            }
+
+            let import = resolve(import_id)
+                .map_err(|error| anyhow!("Failed to resolve import {import_id}: {error}"))?;
+            ensure!(
+                import.id() == import_id,
+                "Resolved program {} instead of import {import_id}",
+                import.id()
+            );
+            path.push(*import_id);
+            Self::visit(import, path, programs, resolve)?;
+            path.pop();
+        }
+        programs.insert(*program.id(), program);
+        Ok(())
+    }
+
+    /// Get the program the graph was built for
+    pub fn root(&self) -> &Program<N> {
+        &self.programs[&self.root]
+    }
+
+    /// Get a program of the graph
+    pub fn get(&self, program_id: &ProgramID<N>) -> Option<&Program<N>> {
+        self.programs.get(program_id)
+    }
+
+    /// Whether a program is part of the graph
+    pub fn contains(&self, program_id: &ProgramID<N>) -> bool {
+        self.programs.contains_key(program_id)
+    }
+
+    /// Whether the root program has imports
+    pub fn has_imports(&self) -> bool {
+        self.programs.len() > 1
+    }
+
+    /// Get the programs directly imported by a program of the graph
+    pub fn direct_imports(&self, program_id: &ProgramID<N>) -> Vec<&Program<N>> {
+        self.programs
+            .get(program_id)
+            .map(|program| {
+                program
+                    .imports()
+                    .keys()
+                    .filter_map(|import_id| self.programs.get(import_id))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get the programs of the graph in deployment order: every program comes after the programs
+    /// it imports, and the root program comes last
+    pub fn topological_order(&self) -> impl Iterator<Item = &Program<N>> {
+        self.programs.values()
+    }
+
+    /// Get the imports of the root program, in deployment order
+    pub fn into_imports(mut self) -> IndexMap<ProgramID<N>, Program<N>> {
+        self.programs.shift_remove(&self.root);
+        self.programs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn program(name: &str, imports: &[&str]) -> Program<Testnet3> {
+        let imports = imports
+            .iter()
+            .map(|import| format!("import {import}.aleo;\n"))
+            .collect::<String>();
+        Program::from_str(&format!(
+            "{imports}
+program {name}.aleo;
+
+function main:
+    input r0 as u32.public;
+    output r0 as u32.public;
+"
+        ))
+        .unwrap()
+    }
+
+    fn resolver<'a>(
+        programs: &[Program<Testnet3>],
+        fetched: &'a mut Vec<String>,
+    ) -> impl FnMut(&ProgramID<Testnet3>) -> Result<Program<Testnet3>> + 'a {
+        let programs = programs
+            .iter()
+            .map(|program| (*program.id(), program.clone()))
+            .collect::<HashMap<_, _>>();
+        move |program_id| {
+            fetched.push(program_id.to_string());
+            programs
+                .get(program_id)
+                .cloned()
+                .ok_or_else(|| anyhow!("Program {program_id} not found"))
+        }
+    }
+
+    #[test]
+    fn test_shared_imports_are_resolved_once_in_topological_order() {
+        let top = program("top", &["left", "right"]);
+        let programs = [
+            program("left", &["base"]),
+            program("right", &["base"]),
+            program("base", &[]),
+        ];
+        let mut fetched = vec![];
+        let graph = ImportGraph::build(&top, resolver(&programs, &mut fetched)).unwrap();
+        assert_eq!(fetched, ["left.aleo", "base.aleo", "right.aleo"]);
+
+        let order = graph
+            .topological_order()
+            .map(|program| program.id().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(order, ["base.aleo", "left.aleo", "right.aleo", "top.aleo"]);
+        assert_eq!(graph.root(), &top);
+        assert!(graph.has_imports());
+        assert_eq!(graph.direct_imports(top.id()).len(), 2);
+        assert_eq!(graph.into_imports().len(), 3);
+    }
+
+    #[test]
+    fn test_cycles_are_reported_with_their_path() {
+        let top = program("top", &["first"]);
+        let programs = [program("first", &["second"]), program("second", &["first"])];
+        let error = ImportGraph::build(&top, resolver(&programs, &mut vec![])).unwrap_err();
+        assert!(error
+            .to_string()
+            .ends_with("first.aleo -> second.aleo -> first.aleo"));
+
+        // Ensure missing imports are reported
+        let error = ImportGraph::build(&top, resolver(&[], &mut vec![])).unwrap_err();
+        assert!(error.to_string().contains("first.aleo"));
+    }
+}
@@ -1,21 +1,48 @@
+pub mod authorization;
+pub use authorization::*;
+
 pub mod deploy;
 
+pub mod deployment_plan;
+pub use deployment_plan::*;
+
 pub mod execute;
 
+pub mod input_validation;
+pub use input_validation::*;
+
+pub mod interface;
+pub use interface::*;
+
+pub mod key_cache;
+pub use key_cache::*;
+
+pub mod mapping;
+pub use mapping::*;
+
 pub mod network;
 
+pub mod records;
+pub use records::*;
+
 pub mod resolver;
 
+pub mod simulate;
+pub use simulate::*;
+
 pub mod transfer;
 pub use transfer::*;
 
+pub mod vm;
+
 use snarkvm::{
     circuit::prelude::IndexMap,
     ledger::store::{helpers::memory::ConsensusMemory, ConsensusStore},
     prelude::*,
 };
 
-use std::path::PathBuf;
+use once_cell::sync::OnceCell;
+use std::{path::PathBuf, sync::Arc};
 
 use crate::aleo_tools::{api::AleoAPIClient, encryptor::Encryptor};
 
@@ -62,6 +89,8 @@ pub struct ProgramManager<N: Network> {
     pub(crate) private_key_ciphertext: Option<Ciphertext<N>>,
     pub(crate) local_program_directory: Option<PathBuf>,
     pub(crate) api_client: Option<AleoAPIClient<N>>,
+    pub(crate) key_cache: Option<KeyCache<N>>,
+    pub(crate) vm: Arc<OnceCell<VM<N, ConsensusMemory<N>>>>,
 }
 
 impl<N: Network> ProgramManager<N> {
@@ -86,6 +115,8 @@ impl<N: Network> ProgramManager<N> {
             private_key_ciphertext,
             local_program_directory,
             api_client,
+            key_cache: None,
+            vm: Default::default(),
         })
     }
 
@@ -0,0 +1,245 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use snarkvm::{
+    ledger::{block::Transaction, query::*},
+    synthesizer::Authorization,
+};
+
+/// Version of the [`AuthorizationBundle`] format produced by this library
+pub const AUTHORIZATION_BUNDLE_VERSION: u8 = 1;
+
+/// A signed but unproven execution, shipped from the device holding the private key to a prover.
+///
+/// The bundle contains the authorization of the function call, the authorization of its fee, and
+/// the programs needed to execute it, so the prover does not need to resolve them. It does not
+/// contain the private key, but it does reveal the inputs of the execution to the prover.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct AuthorizationBundle<N: Network> {
+    pub version: u8,
+    /// ID of the network the execution is authorized for
+    pub network: u16,
+    pub authorization: Authorization<N>,
+    pub fee_authorization: Option<Authorization<N>>,
+    /// The imports of the executed program followed by the program itself, excluding
+    /// `credits.aleo`
+    pub programs: Vec<Program<N>>,
+}
+
+impl<N: Network> AuthorizationBundle<N> {
+    /// Serialize the bundle to JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Deserialize a bundle from JSON, checking it was made for this network by a supported
+    /// version of the format
+    pub fn from_json(json: &str) -> Result<Self> {
+        let bundle: Self = serde_json::from_str(json)?;
+        ensure!(
+            bundle.version == AUTHORIZATION_BUNDLE_VERSION,
+            "Unsupported authorization bundle version {}",
+            bundle.version
+        );
+        ensure!(
+            bundle.network == N::ID,
+            "The authorization bundle was made for network {} instead of network {}",
+            bundle.network,
+            N::ID
+        );
+        Ok(bundle)
+    }
+}
+
+impl<N: Network> ProgramManager<N> {
+    /// Authorize the execution of a program function and its fee without proving it, so the
+    /// proving can be delegated to another device with [`ProgramManager::prove_authorization`].
+    ///
+    /// Since the execution is not proven, its cost cannot be computed, so the base fee must be
+    /// specified, e.g. from [`ProgramManager::estimate_execution_fee`]. The fee is paid with the
+    /// fee record if one is specified, or from the public balance otherwise. A base fee of 0
+    /// authorizes no fee.
+    #[allow(clippy::too_many_arguments)]
+    pub fn authorize_execution(
+        &self,
+        program_id: impl TryInto<ProgramID<N>>,
+        function: impl TryInto<Identifier<N>>,
+        inputs: impl ExactSizeIterator<Item = impl TryInto<Value<N>>>,
+        base_fee: u64,
+        priority_fee: u64,
+        fee_record: Option<Record<N, Plaintext<N>>>,
+        password: Option<&str>,
+    ) -> Result<AuthorizationBundle<N>> {
+        let program_id = program_id
+            .try_into()
+            .map_err(|_| anyhow!("Invalid program ID"))?;
+        let function_name = function
+            .try_into()
+            .map_err(|_| anyhow!("Invalid function name"))?;
+
+        // Resolve the program and its imports, and load them into the VM of the program manager
+        let credits_id = ProgramID::<N>::from_str("credits.aleo")?;
+        let mut programs = vec![];
+        let vm = match program_id == credits_id {
+            true => self.vm()?,
+            false => {
+                let program = match self.get_program(program_id) {
+                    Ok(program) => program,
+                    Err(_) => self.api_client()?.get_program(program_id)?,
+                };
+                let imports = self
+                    .api_client()?
+                    .get_program_imports_from_source(&program)?;
+                programs.extend(
+                    imports
+                        .into_values()
+                        .filter(|import| import.id() != &credits_id),
+                );
+                programs.push(program);
+                self.load_program_into_vm(programs.last().unwrap())?
+            }
+        };
+
+        let private_key = self.get_private_key(password)?;
+        let rng = &mut rand::thread_rng();
+
+        println!("Authorizing execution of function {function_name:?} from program {program_id:?}");
+        let authorization = vm.authorize(&private_key, program_id, function_name, inputs, rng)?;
+        let fee_authorization = match base_fee {
+            0 => None,
+            _ => {
+                let execution_id = authorization.to_execution_id()?;
+                Some(match fee_record {
+                    Some(fee_record) => vm.authorize_fee_private(
+                        &private_key,
+                        fee_record,
+                        base_fee,
+                        priority_fee,
+                        execution_id,
+                        rng,
+                    )?,
+                    None => vm.authorize_fee_public(
+                        &private_key,
+                        base_fee,
+                        priority_fee,
+                        execution_id,
+                        rng,
+                    )?,
+                })
+            }
+        };
+
+        Ok(AuthorizationBundle {
+            version: AUTHORIZATION_BUNDLE_VERSION,
+            network: N::ID,
+            authorization,
+            fee_authorization,
+            programs,
+        })
+    }
+
+    /// Authorize a transfer without proving it, see [`ProgramManager::authorize_execution`]
+    #[allow(clippy::too_many_arguments)]
+    pub fn authorize_transfer(
+        &self,
+        amount: u64,
+        base_fee: u64,
+        priority_fee: u64,
+        recipient_address: Address<N>,
+        transfer_type: TransferType,
+        amount_record: Option<Record<N, Plaintext<N>>>,
+        fee_record: Option<Record<N, Plaintext<N>>>,
+        password: Option<&str>,
+    ) -> Result<AuthorizationBundle<N>> {
+        let (function, inputs) =
+            Self::transfer_inputs(transfer_type, recipient_address, amount, amount_record)?;
+        self.authorize_execution(
+            "credits.aleo",
+            function,
+            inputs.into_iter(),
+            base_fee,
+            priority_fee,
+            fee_record,
+            password,
+        )
+    }
+
+    /// Prove an authorized execution, turning it into a transaction ready to be broadcast. This
+    /// is meant to run on the proving side, which needs no private key. The node of the API
+    /// client is queried for the state of the ledger the proofs are made against.
+    pub fn prove_authorization(
+        bundle: AuthorizationBundle<N>,
+        api_client: &AleoAPIClient<N>,
+    ) -> Result<Transaction<N>> {
+        ensure!(
+            bundle.network == N::ID,
+            "The authorization bundle was made for network {} instead of network {}",
+            bundle.network,
+            N::ID
+        );
+        let vm = Self::vm_with_programs(&bundle.programs)?;
+        let query = Query::from(api_client.base_url());
+        vm.execute_authorization(
+            bundle.authorization,
+            bundle.fee_authorization,
+            Some(query),
+            &mut rand::thread_rng(),
+        )
+    }
+
+    // Initialize a VM with the given programs, which must be ordered so imports come first
+    fn vm_with_programs(programs: &[Program<N>]) -> Result<VM<N, ConsensusMemory<N>>> {
+        let store = ConsensusStore::<N, ConsensusMemory<N>>::open(None)?;
+        let vm = VM::<N, ConsensusMemory<N>>::from(store)?;
+        for program in programs {
+            if !vm.process().read().contains_program(program.id()) {
+                vm.process().write().add_program(program)?;
+            }
+        }
+        Ok(vm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aleo_tools::test_utils::MockLedger;
+    use crate::models::constants::{TESTNET3_ADDRESS, TESTNET_PRIVATE_KEY};
+
+    #[test]
+    fn test_authorization_bundles_round_trip() {
+        let private_key = PrivateKey::<Testnet3>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        let program_manager =
+            ProgramManager::<Testnet3>::new(Some(private_key), None, Some(ledger.client()), None)
+                .unwrap();
+        let recipient = Address::<Testnet3>::from_str(TESTNET3_ADDRESS).unwrap();
+
+        let bundle = program_manager
+            .authorize_transfer(
+                100,
+                5_000,
+                0,
+                recipient,
+                TransferType::Public,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        assert!(bundle.programs.is_empty());
+        assert!(bundle.fee_authorization.is_some());
+
+        // Ensure the bundle survives serialization
+        let json = bundle.to_json().unwrap();
+        let imported = AuthorizationBundle::<Testnet3>::from_json(&json).unwrap();
+        assert_eq!(imported.to_json().unwrap(), json);
+
+        // Ensure bundles of unknown versions are rejected
+        let bundle = AuthorizationBundle {
+            version: 0,
+            ..bundle
+        };
+        assert!(AuthorizationBundle::<Testnet3>::from_json(&bundle.to_json().unwrap()).is_err());
+    }
+}
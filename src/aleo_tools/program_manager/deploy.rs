@@ -13,6 +13,39 @@ impl<N: Network> ProgramManager<N> {
         fee_record: Option<Record<N, Plaintext<N>>>,
         password: Option<&str>,
     ) -> Result<N::TransactionID> {
+        let transaction = self.build_deployment(program_id, priority_fee, fee_record, password)?;
+        let program_id = *transaction
+            .deployment()
+            .ok_or_else(|| anyhow!("❌ Transaction is not a deployment transaction"))?
+            .program_id();
+
+        println!(
+            "Attempting to broadcast a deploy transaction for program {:?} to node {:?}",
+            program_id,
+            self.api_client()?.base_url()
+        );
+
+        let result = self.broadcast_transaction(transaction.clone());
+
+        // Notify the developer of the result
+        if result.is_ok() {
+            println!("✅ Deployment transaction for {program_id:?} broadcast successfully");
+        } else {
+            println!("❌ Deployment transaction for {program_id:?} failed to broadcast");
+        };
+
+        Ok(transaction.id())
+    }
+
+    /// Build and sign a transaction deploying a program without broadcasting it, so it can be
+    /// inspected, stored or broadcast later with [`ProgramManager::broadcast_transaction`]
+    pub fn build_deployment(
+        &self,
+        program_id: impl TryInto<ProgramID<N>>,
+        priority_fee: u64,
+        fee_record: Option<Record<N, Plaintext<N>>>,
+        password: Option<&str>,
+    ) -> Result<Transaction<N>> {
         // Ensure a network client is configured, otherwise deployment is not possible
         ensure!(
             self.api_client.is_some(),
@@ -32,27 +65,7 @@ impl<N: Network> ProgramManager<N> {
         );
 
         // Get the program if it already exists, otherwise find it
-        println!("Loading program {program_id:?}..");
-        let program = if let Ok(program) = self.get_program(program_id) {
-            println!(
-                "Program {:?} already exists in program manager, using existing program",
-                program_id
-            );
-            program
-        } else if let Some(dir) = self.local_program_directory.as_ref() {
-            let program = self.find_program_on_disk(&program_id);
-            if program.is_err() {
-                bail!(
-                    "❌ Program {program_id:?} could not be found at {dir:?} or in the program manager, please ensure the program is in the correct directory before continuing with deployment"
-                );
-            }
-            program?
-        } else {
-            bail!(
-                "❌ Program {:?} not found in program manager and no local program directory was configured",
-                program_id
-            );
-        };
+        let program = self.find_local_program(&program_id)?;
 
         // If the program has imports, check if they are deployed on chain. If they are not or if
         // the imports on disk or in-memory do not match the programs deployed on chain, cancel deployment
@@ -69,7 +82,7 @@ impl<N: Network> ProgramManager<N> {
             let imported_program_id = imported_program.id();
             match self.on_chain_program_state(&imported_program)? {
                 OnChainProgramState::NotDeployed => {
-                    // For now enforce that users deploy imports individually. In the future, create a more detailed program resolution method for local imports
+                    // Imports missing on chain are deployed first by ProgramManager::deploy_with_imports
                     bail!("❌ Imported program {imported_program_id:?} could not be found on the Aleo Network, please deploy this imported program first before continuing with deployment of {program_id:?}");
                 }
                 OnChainProgramState::Different => {
@@ -85,34 +98,47 @@ impl<N: Network> ProgramManager<N> {
         // Try to get the private key
         let private_key = self.get_private_key(password)?;
 
-        // Attempt to construct the transaction
+        // Attempt to construct the transaction with the imports loaded into the VM of the program
+        // manager
         println!("Building transaction..");
-        let query = self.api_client.as_ref().unwrap().base_url();
-        let transaction = Self::create_deploy_transaction(
-            &program,
+        let query = Query::from(self.api_client()?.base_url());
+        let vm = self.load_imports_into_vm(&program)?;
+        let transaction = vm.deploy(
             &private_key,
-            priority_fee,
+            &program,
             fee_record,
-            query.to_string(),
-            self.api_client()?,
+            priority_fee,
+            Some(query),
+            &mut rand::thread_rng(),
         )?;
 
-        println!(
-            "Attempting to broadcast a deploy transaction for program {:?} to node {:?}",
-            program_id,
-            self.api_client().unwrap().base_url()
-        );
-
-        let result = self.broadcast_transaction(transaction.clone());
+        Ok(transaction)
+    }
 
-        // Notify the developer of the result
-        if result.is_ok() {
-            println!("✅ Deployment transaction for {program_id:?} broadcast successfully");
+    /// Get a program to deploy from the program manager if it was added to it, otherwise from
+    /// the local program directory
+    pub(super) fn find_local_program(&self, program_id: &ProgramID<N>) -> Result<Program<N>> {
+        println!("Loading program {program_id:?}..");
+        if let Ok(program) = self.get_program(*program_id) {
+            println!(
+                "Program {:?} already exists in program manager, using existing program",
+                program_id
+            );
+            Ok(program)
+        } else if let Some(dir) = self.local_program_directory.as_ref() {
+            let program = self.find_program_on_disk(program_id);
+            if program.is_err() {
+                bail!(
+                    "❌ Program {program_id:?} could not be found at {dir:?} or in the program manager, please ensure the program is in the correct directory before continuing with deployment"
+                );
+            }
+            program
         } else {
-            println!("❌ Deployment transaction for {program_id:?} failed to broadcast");
-        };
-
-        Ok(transaction.id())
+            bail!(
+                "❌ Program {:?} not found in program manager and no local program directory was configured",
+                program_id
+            );
+        }
     }
 
     /// Create a deploy transaction for a program without instantiating the program manager
@@ -153,7 +179,7 @@ impl<N: Network> ProgramManager<N> {
         program: &Program<N>,
         private_key: &PrivateKey<N>,
     ) -> Result<(u64, (u64, u64))> {
-        let vm = Self::initialize_vm(self.api_client()?, program, false)?;
+        let vm = self.load_imports_into_vm(program)?;
         let tx = vm.deploy(
             private_key,
             program,
@@ -0,0 +1,222 @@
+use super::*;
+use crate::aleo_tools::{
+    fee_estimator::FeeEstimator, import_graph::ImportGraph, tracker::TransactionTracker,
+};
+use crate::models::encrypted_data::TransactionState;
+
+use std::fmt;
+
+/// A deployment of a deployment plan
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlannedDeployment<N: Network> {
+    pub program: Program<N>,
+    /// Estimated deployment fee in microcredits, excluding the priority fee
+    pub estimated_fee: u64,
+}
+
+/// The deployments needed to deploy a local program along with the local imports which are not
+/// deployed on chain yet
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeploymentPlan<N: Network> {
+    /// Imports which already match the programs deployed on chain
+    pub deployed_imports: Vec<ProgramID<N>>,
+    /// Deployments in the order they must be made: the missing imports in topological order,
+    /// followed by the program itself
+    pub deployments: Vec<PlannedDeployment<N>>,
+}
+
+impl<N: Network> DeploymentPlan<N> {
+    /// Get the total estimated fee of the plan in microcredits, excluding priority fees
+    pub fn total_fee(&self) -> u64 {
+        self.deployments
+            .iter()
+            .map(|deployment| deployment.estimated_fee)
+            .sum()
+    }
+}
+
+impl<N: Network> fmt::Display for DeploymentPlan<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Deployment plan:")?;
+        for program_id in &self.deployed_imports {
+            writeln!(f, "  - {program_id} is already deployed")?;
+        }
+        for (step, deployment) in self.deployments.iter().enumerate() {
+            writeln!(
+                f,
+                "  {}. Deploy {} (estimated fee: {} microcredits)",
+                step + 1,
+                deployment.program.id(),
+                deployment.estimated_fee
+            )?;
+        }
+        write!(f, "Total estimated fee: {} microcredits", self.total_fee())
+    }
+}
+
+impl<N: Network> ProgramManager<N> {
+    /// Plan the deployment of a local program, resolving its imports in memory, on disk or on
+    /// chain. Local imports which are not deployed on chain are planned for deployment before the
+    /// program, every import before the programs importing it. Fees are estimated with a
+    /// [`FeeEstimator`], without synthesizing the keys of the programs, so planning is cheap
+    /// compared to building the deployments.
+    ///
+    /// Disclaimer: Fee estimation is experimental and may not represent a correct estimate on any current or future network
+    pub fn plan_deployment(
+        &self,
+        program_id: impl TryInto<ProgramID<N>>,
+    ) -> Result<DeploymentPlan<N>> {
+        let program_id = program_id
+            .try_into()
+            .map_err(|_| anyhow!("Invalid program ID"))?;
+        let program = self.find_local_program(&program_id)?;
+        ensure!(
+            self.on_chain_program_state(&program)? == OnChainProgramState::NotDeployed,
+            "❌ Program {program_id:?} already deployed on chain, cancelling deployment"
+        );
+
+        let credits = Program::<N>::credits()?;
+        let graph = ImportGraph::build(&program, |import_id| {
+            if import_id == credits.id() {
+                Ok(credits.clone())
+            } else if self.contains_program(import_id)? {
+                self.get_program(import_id)
+            } else {
+                self.find_program(import_id)
+            }
+        })?;
+
+        let mut plan = DeploymentPlan {
+            deployed_imports: vec![],
+            deployments: vec![],
+        };
+        for program in graph.topological_order() {
+            let program_id = program.id();
+            if program_id == credits.id() {
+                continue;
+            }
+            let state = match program_id == graph.root().id() {
+                true => OnChainProgramState::NotDeployed,
+                false => self.on_chain_program_state(program)?,
+            };
+            match state {
+                OnChainProgramState::Same => plan.deployed_imports.push(*program_id),
+                OnChainProgramState::Different => {
+                    bail!("❌ Imported program {program_id:?} is already deployed on chain and did not match local import");
+                }
+                OnChainProgramState::NotDeployed => {
+                    let (estimated_fee, _) =
+                        FeeEstimator::new(program, graph.topological_order().cloned())?
+                            .estimate_deployment_fee()?;
+                    plan.deployments.push(PlannedDeployment {
+                        program: program.clone(),
+                        estimated_fee,
+                    });
+                }
+            }
+        }
+        Ok(plan)
+    }
+
+    /// Deploy a local program along with its local imports which are not deployed on chain yet,
+    /// following [`ProgramManager::plan_deployment`]. Every import is broadcast and confirmed
+    /// before the next deployment is built, and deployment stops at the first import which is
+    /// not confirmed. Fees are paid from the public balance of the account.
+    ///
+    /// In dry run mode the plan and its total estimated fee are printed and nothing is deployed.
+    /// Returns the IDs of the deployment transactions broadcast, the program's last.
+    pub fn deploy_with_imports(
+        &mut self,
+        program_id: impl TryInto<ProgramID<N>>,
+        priority_fee: u64,
+        password: Option<&str>,
+        dry_run: bool,
+    ) -> Result<Vec<N::TransactionID>> {
+        let plan = self.plan_deployment(program_id)?;
+        println!("{plan}");
+        if dry_run {
+            return Ok(vec![]);
+        }
+
+        let tracker = TransactionTracker::new(self.api_client()?.clone());
+        let mut transaction_ids = vec![];
+        for (step, deployment) in plan.deployments.iter().enumerate() {
+            let program_id = *deployment.program.id();
+            let transaction = self.build_deployment(program_id, priority_fee, None, password)?;
+            let transaction_id = transaction.id();
+            if let Err(error) = self.broadcast_transaction(transaction) {
+                bail!("❌ Deployment transaction for {program_id:?} failed to broadcast: {error}");
+            }
+            println!("✅ Deployment transaction for {program_id:?} broadcast successfully");
+            transaction_ids.push(transaction_id);
+
+            // The program itself is not waited for, since nothing is deployed after it
+            if step + 1 == plan.deployments.len() {
+                break;
+            }
+            println!("Waiting for the deployment of {program_id:?} to be confirmed..");
+            let status = tracker.track(transaction_id, |status| {
+                println!("Deployment of {program_id:?}: {:?}", status.state)
+            })?;
+            ensure!(
+                status.state == TransactionState::Confirmed,
+                "❌ Deployment of {program_id:?} ended in state {:?}, cancelling the remaining deployments",
+                status.state
+            );
+        }
+        Ok(transaction_ids)
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(feature = "wasm"))]
+mod tests {
+    use super::*;
+    use crate::aleo_tools::test_utils::{
+        setup_directory, MockLedger, MULTIPLY_IMPORT_PROGRAM, MULTIPLY_PROGRAM,
+    };
+    use crate::models::constants::TESTNET_PRIVATE_KEY;
+
+    #[test]
+    fn test_undeployed_imports_are_planned_first() {
+        let private_key = PrivateKey::<Testnet3>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        let directory = setup_directory(
+            "aleo_test_deployment_plan",
+            MULTIPLY_IMPORT_PROGRAM,
+            vec![("multiply_test.aleo", MULTIPLY_PROGRAM)],
+        )
+        .unwrap();
+        let program_manager = ProgramManager::<Testnet3>::new(
+            Some(private_key),
+            None,
+            Some(ledger.client()),
+            Some(directory),
+        )
+        .unwrap();
+
+        let plan = program_manager.plan_deployment("double_test.aleo").unwrap();
+        let order = plan
+            .deployments
+            .iter()
+            .map(|deployment| deployment.program.id().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(order, ["multiply_test.aleo", "double_test.aleo"]);
+        assert!(plan
+            .deployments
+            .iter()
+            .all(|deployment| deployment.estimated_fee > 0));
+        assert!(plan.deployed_imports.is_empty());
+        assert!(plan.to_string().ends_with(&format!(
+            "Total estimated fee: {} microcredits",
+            plan.total_fee()
+        )));
+
+        // Ensure imports deployed on chain are not deployed again
+        let multiply_program = Program::<Testnet3>::from_str(MULTIPLY_PROGRAM).unwrap();
+        ledger.add_program(multiply_program.clone());
+        let plan = program_manager.plan_deployment("double_test.aleo").unwrap();
+        assert_eq!(plan.deployed_imports, vec![*multiply_program.id()]);
+        assert_eq!(plan.deployments.len(), 1);
+    }
+}
@@ -32,6 +32,52 @@ impl<N: Network> ProgramManager<N> {
         fee_record: Option<Record<N, Plaintext<N>>>,
         password: Option<&str>,
     ) -> Result<N::TransactionID> {
+        // Check program and function have valid names
+        let program_id = program_id
+            .try_into()
+            .map_err(|_| anyhow!("Invalid program ID"))?;
+        let function_id = function
+            .try_into()
+            .map_err(|_| anyhow!("Invalid function name"))?;
+        let function_name = function_id.to_string();
+
+        // Create the execution transaction
+        let transaction = self.build_execution(
+            program_id,
+            function_id,
+            inputs,
+            priority_fee,
+            fee_record,
+            password,
+        )?;
+
+        // Broadcast the execution transaction to the network
+        println!("Attempting to broadcast execution transaction for {program_id:?}");
+        let execution = self.broadcast_transaction(transaction.clone());
+
+        // Tell the user about the result of the execution before returning it
+        if execution.is_ok() {
+            println!("✅ Execution of function {function_name:?} from program {program_id:?}' broadcast successfully");
+        } else {
+            println!("❌ Execution of function {function_name:?} from program {program_id:?} failed to broadcast");
+        }
+
+        Ok(transaction.id())
+    }
+
+    /// Build and sign a transaction executing a program function without broadcasting it, so it
+    /// can be inspected, stored or broadcast later with [`ProgramManager::broadcast_transaction`]
+    ///
+    /// The program must already be deployed on the Aleo Network
+    pub fn build_execution(
+        &self,
+        program_id: impl TryInto<ProgramID<N>>,
+        function: impl TryInto<Identifier<N>>,
+        inputs: impl ExactSizeIterator<Item = impl TryInto<Value<N>>>,
+        priority_fee: u64,
+        fee_record: Option<Record<N, Plaintext<N>>>,
+        password: Option<&str>,
+    ) -> Result<Transaction<N>> {
         // Ensure a network client is set, otherwise online execution is not possible
         ensure!(
             self.api_client.is_some(),
@@ -45,7 +91,6 @@ impl<N: Network> ProgramManager<N> {
         let function_id = function
             .try_into()
             .map_err(|_| anyhow!("Invalid function name"))?;
-        let function_name = function_id.to_string();
 
         // Get the program from chain, error if it doesn't exist
         let program = self
@@ -53,32 +98,25 @@ impl<N: Network> ProgramManager<N> {
             .get_program(program_id)
             .map_err(|_| anyhow!("Program {program_id:?} does not exist on the Aleo Network. Try deploying the program first before executing."))?;
 
+        // Load the program into the VM of the program manager
+        let function_name = Self::check_function(&program, function_id)?;
+        let inputs = InputValidator::new(&program, function_name)?.validate(inputs)?;
+        let vm = self.load_program_into_vm(&program)?;
+
         // Create the execution transaction
         let private_key = self.get_private_key(password)?;
-        let node_url = self.api_client.as_ref().unwrap().base_url().to_string();
-        let transaction = Self::create_execute_transaction(
+        let node_url = self.api_client()?.base_url().to_string();
+        Self::execute_in_vm(
+            vm,
             &private_key,
             priority_fee,
-            inputs,
+            inputs.into_iter(),
             fee_record,
             &program,
-            function_id,
+            function_name,
             node_url,
-            self.api_client()?,
-        )?;
-
-        // Broadcast the execution transaction to the network
-        println!("Attempting to broadcast execution transaction for {program_id:?}");
-        let execution = self.broadcast_transaction(transaction.clone());
-
-        // Tell the user about the result of the execution before returning it
-        if execution.is_ok() {
-            println!("✅ Execution of function {function_name:?} from program {program_id:?}' broadcast successfully");
-        } else {
-            println!("❌ Execution of function {function_name:?} from program {program_id:?} failed to broadcast");
-        }
-
-        Ok(transaction.id())
+            self.key_cache.as_ref(),
+        )
     }
 
     /// Create an execute transaction without initializing a program manager instance
@@ -93,11 +131,30 @@ impl<N: Network> ProgramManager<N> {
         node_url: String,
         api_client: &AleoAPIClient<N>,
     ) -> Result<Transaction<N>> {
-        // Initialize an RNG and query object for the transaction
-        let rng = &mut rand::thread_rng();
-        let query = Query::from(node_url);
+        let function_name = Self::check_function(program, function)?;
 
-        // Check that the function exists in the program
+        // Initialize the VM
+        let vm = Self::initialize_vm(api_client, program, true)?;
+
+        // Create an execution transaction
+        Self::execute_in_vm(
+            &vm,
+            private_key,
+            priority_fee,
+            inputs,
+            fee_record,
+            program,
+            function_name,
+            node_url,
+            None,
+        )
+    }
+
+    // Check that the function exists in the program
+    pub(super) fn check_function(
+        program: &Program<N>,
+        function: impl TryInto<Identifier<N>>,
+    ) -> Result<Identifier<N>> {
         let function_name = function
             .try_into()
             .map_err(|_| anyhow!("Invalid function name"))?;
@@ -107,20 +164,46 @@ impl<N: Network> ProgramManager<N> {
             program.contains_function(&function_name),
             "Program {program_id:?} does not contain function {function_name:?}, aborting execution"
         );
+        Ok(function_name)
+    }
 
-        // Initialize the VM
-        let vm = Self::initialize_vm(api_client, program, true)?;
+    // Create an execute transaction with a VM the program is loaded into, loading the keys of the
+    // program and its imports from the key cache if one is given, and storing the keys
+    // synthesized during the execution in it
+    #[allow(clippy::too_many_arguments)]
+    fn execute_in_vm(
+        vm: &VM<N, ConsensusMemory<N>>,
+        private_key: &PrivateKey<N>,
+        priority_fee: u64,
+        inputs: impl ExactSizeIterator<Item = impl TryInto<Value<N>>>,
+        fee_record: Option<Record<N, Plaintext<N>>>,
+        program: &Program<N>,
+        function_name: Identifier<N>,
+        node_url: String,
+        key_cache: Option<&KeyCache<N>>,
+    ) -> Result<Transaction<N>> {
+        // Initialize an RNG and query object for the transaction
+        let rng = &mut rand::thread_rng();
+        let query = Query::from(node_url);
+
+        if let Some(key_cache) = key_cache {
+            key_cache.load_into(&vm.process().read(), program)?;
+        }
 
         // Create an execution transaction
-        vm.execute(
+        let transaction = vm.execute(
             private_key,
-            (program_id, function_name),
+            (program.id(), function_name),
             inputs,
             fee_record,
             priority_fee,
             Some(query),
             rng,
-        )
+        )?;
+        if let Some(key_cache) = key_cache {
+            key_cache.store_from(&vm.process().read(), program)?;
+        }
+        Ok(transaction)
     }
 
     /// Estimate the cost of executing a program with the given inputs in microcredits. The response
@@ -149,11 +232,20 @@ impl<N: Network> ProgramManager<N> {
             "Program {program_id:?} does not contain function {function_name:?}, aborting execution"
         );
 
-        // Create an ephemeral SnarkVM to store the programs
         // Initialize an RNG and query object for the transaction
         let rng = &mut rand::thread_rng();
         let query = Query::<N, BlockMemory<N>>::from(url);
-        let vm = Self::initialize_vm(self.api_client()?, program, true)?;
+
+        // Load the program into the VM of the program manager, or into a throwaway VM if a
+        // different version of it is loaded, so the shared VM can still execute that version
+        let throwaway_vm = self.throwaway_vm_for(program)?;
+        let vm = match &throwaway_vm {
+            Some(vm) => vm,
+            None => self.load_program_into_vm(program)?,
+        };
+        if let Some(key_cache) = &self.key_cache {
+            key_cache.load_into(&vm.process().read(), program)?;
+        }
 
         // Create an ephemeral private key for the sample execution
         let private_key = PrivateKey::<N>::new(rng)?;
@@ -169,8 +261,13 @@ impl<N: Network> ProgramManager<N> {
         trace.prepare(query)?;
         let execution =
             trace.prove_execution::<A, _>(&locator.to_string(), &mut rand::thread_rng())?;
+        // Keys of a throwaway version are not cached, since storing them evicts the cached keys
+        // of the version loaded into the shared VM
+        if let (Some(key_cache), None) = (&self.key_cache, &throwaway_vm) {
+            key_cache.store_from(&vm.process().read(), program)?;
+        }
         // Add the fee data to the Avail Fee Estimation Microservice
-        let (fee, (_storage_fee, _namespace_fee)) = execution_cost(&vm, &execution)?;
+        let (fee, (_storage_fee, _namespace_fee)) = execution_cost(vm, &execution)?;
         Ok((fee, (_storage_fee, _namespace_fee), execution.clone()))
     }
     /// Estimate the finalize fee component for executing a function. This fee is additional to the
@@ -0,0 +1,505 @@
+use super::*;
+use crate::errors::{AvailError, AvailErrorType, AvailResult};
+
+use serde_json::Value as JsonValue;
+use std::fmt;
+
+/// An argument which does not match the input declared for it by a function
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArgumentError {
+    /// The position of the argument
+    pub index: usize,
+    /// The declared input, e.g. `u64.public`
+    pub expected: String,
+    pub message: String,
+}
+
+impl fmt::Display for ArgumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "input {} (expected {}): {}",
+            self.index, self.expected, self.message
+        )
+    }
+}
+
+/// The arguments of a function call which do not match the inputs declared by the function
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputValidationError {
+    pub program_id: String,
+    pub function_name: String,
+    pub errors: Vec<ArgumentError>,
+}
+
+impl fmt::Display for InputValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors = self
+            .errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        write!(
+            f,
+            "Invalid inputs for {}/{}: {}",
+            self.program_id,
+            self.function_name,
+            errors.join("; ")
+        )
+    }
+}
+
+impl std::error::Error for InputValidationError {}
+
+impl From<InputValidationError> for AvailError {
+    fn from(value: InputValidationError) -> Self {
+        let external_msg = value
+            .errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        Self {
+            error_type: AvailErrorType::Validation,
+            internal_msg: value.to_string(),
+            external_msg,
+        }
+    }
+}
+
+/// Validator checking the arguments of a function call against the inputs declared by the
+/// function, before anything is executed.
+///
+/// Arguments are checked for their count, their types, including the members of structs and the
+/// entries of records, and the visibility of record entries. Arguments given as strings or JSON
+/// are coerced into values of the declared types, e.g. `5` or `"5"` into `5u64` for a `u64` input,
+/// or `{"x": 1, "y": 2}` into `{ x: 1u32, y: 2u32 }` for a struct input.
+#[derive(Clone, Debug)]
+pub struct InputValidator<'a, N: Network> {
+    program: &'a Program<N>,
+    function: Function<N>,
+}
+
+impl<'a, N: Network> InputValidator<'a, N> {
+    pub fn new(program: &'a Program<N>, function: impl TryInto<Identifier<N>>) -> Result<Self> {
+        let function_name = function
+            .try_into()
+            .map_err(|_| anyhow!("Invalid function name"))?;
+        let function = program.get_function(&function_name)?;
+        Ok(Self { program, function })
+    }
+
+    /// Check values against the inputs of the function
+    pub fn validate(
+        &self,
+        inputs: impl IntoIterator<Item = impl TryInto<Value<N>>>,
+    ) -> Result<Vec<Value<N>>, InputValidationError> {
+        self.validate_with(inputs, |input, value_type| {
+            let value = input
+                .try_into()
+                .map_err(|_| "the argument is not a valid value".to_string())?;
+            self.check_value(&value, value_type)?;
+            Ok(value)
+        })
+    }
+
+    /// Coerce strings into values of the inputs of the function. Literals may be given without
+    /// their type.
+    pub fn validate_strings(
+        &self,
+        inputs: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<Vec<Value<N>>, InputValidationError> {
+        self.validate_with(inputs, |input, value_type| {
+            self.coerce_str(input.as_ref(), value_type)
+        })
+    }
+
+    /// Coerce JSON values into values of the inputs of the function. Literals are given as JSON
+    /// numbers, booleans or strings, structs as objects and arrays as arrays, and records as
+    /// strings.
+    pub fn validate_json(
+        &self,
+        inputs: impl IntoIterator<Item = JsonValue>,
+    ) -> Result<Vec<Value<N>>, InputValidationError> {
+        self.validate_with(inputs, |input, value_type| {
+            self.coerce_json(&input, value_type)
+        })
+    }
+
+    // Convert every argument with the given function, collecting the errors of all arguments
+    fn validate_with<T>(
+        &self,
+        inputs: impl IntoIterator<Item = T>,
+        mut convert: impl FnMut(T, &ValueType<N>) -> Result<Value<N>, String>,
+    ) -> Result<Vec<Value<N>>, InputValidationError> {
+        let declared = self.function.inputs();
+        let mut values = vec![];
+        let mut errors = vec![];
+        let mut count = 0;
+        for (index, input) in inputs.into_iter().enumerate() {
+            count += 1;
+            let Some(declared_input) = declared.get_index(index) else {
+                errors.push(ArgumentError {
+                    index,
+                    expected: "no input".to_string(),
+                    message: format!("the function takes {} inputs", declared.len()),
+                });
+                continue;
+            };
+            let value_type = declared_input.value_type();
+            match convert(input, value_type) {
+                Ok(value) => values.push(value),
+                Err(message) => errors.push(ArgumentError {
+                    index,
+                    expected: value_type.to_string(),
+                    message,
+                }),
+            }
+        }
+        for (index, declared_input) in declared.iter().enumerate().skip(count) {
+            errors.push(ArgumentError {
+                index,
+                expected: declared_input.value_type().to_string(),
+                message: "the argument is missing".to_string(),
+            });
+        }
+
+        match errors.is_empty() {
+            true => Ok(values),
+            false => Err(InputValidationError {
+                program_id: self.program.id().to_string(),
+                function_name: self.function.name().to_string(),
+                errors,
+            }),
+        }
+    }
+
+    fn coerce_str(&self, input: &str, value_type: &ValueType<N>) -> Result<Value<N>, String> {
+        let input = input.trim();
+        if let Ok(value) = Value::from_str(input) {
+            self.check_value(&value, value_type)?;
+            return Ok(value);
+        }
+        // Literals may be given without their type, e.g. `5` for a `u64` input
+        if let ValueType::Constant(PlaintextType::Literal(literal_type))
+        | ValueType::Public(PlaintextType::Literal(literal_type))
+        | ValueType::Private(PlaintextType::Literal(literal_type)) = value_type
+        {
+            if let Ok(value) = Value::from_str(&format!("{input}{literal_type}")) {
+                return Ok(value);
+            }
+        }
+        Err(format!("'{input}' is not a valid {value_type}"))
+    }
+
+    fn coerce_json(
+        &self,
+        input: &JsonValue,
+        value_type: &ValueType<N>,
+    ) -> Result<Value<N>, String> {
+        let input = match (value_type, input) {
+            (
+                ValueType::Constant(plaintext_type)
+                | ValueType::Public(plaintext_type)
+                | ValueType::Private(plaintext_type),
+                _,
+            ) => Self::json_to_plaintext(self.program, input, plaintext_type)?,
+            (ValueType::Record(..) | ValueType::ExternalRecord(..), JsonValue::String(record)) => {
+                record.clone()
+            }
+            _ => return Err(format!("{input} is not a valid {value_type}")),
+        };
+        self.coerce_str(&input, value_type)
+    }
+
+    // Convert a JSON value into the string of a plaintext of the given type, whose structs are
+    // declared by the given program
+    pub(super) fn json_to_plaintext(
+        program: &Program<N>,
+        input: &JsonValue,
+        plaintext_type: &PlaintextType<N>,
+    ) -> Result<String, String> {
+        match (plaintext_type, input) {
+            (PlaintextType::Literal(LiteralType::String), JsonValue::String(string)) => {
+                Ok(format!("{string:?}"))
+            }
+            (PlaintextType::Literal(literal_type), JsonValue::Number(number)) => {
+                Ok(format!("{number}{literal_type}"))
+            }
+            // Numbers may be given as strings without their type, e.g. `"5"` for a `u64`
+            (PlaintextType::Literal(literal_type), JsonValue::String(string))
+                if is_bare_number(string.trim()) =>
+            {
+                Ok(format!("{}{literal_type}", string.trim()))
+            }
+            (PlaintextType::Literal(_), JsonValue::Bool(boolean)) => Ok(boolean.to_string()),
+            (PlaintextType::Struct(struct_name), JsonValue::Object(members)) => {
+                let struct_type = program
+                    .get_struct(struct_name)
+                    .map_err(|error| error.to_string())?;
+                if let Some(unknown) = members.keys().find(|name| {
+                    !struct_type
+                        .members()
+                        .keys()
+                        .any(|member_name| member_name.to_string() == **name)
+                }) {
+                    return Err(format!("struct {struct_name} has no member {unknown}"));
+                }
+                let members = struct_type
+                    .members()
+                    .iter()
+                    .map(|(member_name, member_type)| {
+                        let member = members
+                            .get(&member_name.to_string())
+                            .ok_or_else(|| format!("member {member_name} is missing"))?;
+                        let member = Self::json_to_plaintext(program, member, member_type)
+                            .map_err(|error| format!("{member_name}: {error}"))?;
+                        Ok(format!("{member_name}: {member}"))
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+                Ok(format!("{{ {} }}", members.join(", ")))
+            }
+            (PlaintextType::Array(array_type), JsonValue::Array(elements)) => {
+                let elements = elements
+                    .iter()
+                    .map(|element| {
+                        Self::json_to_plaintext(program, element, array_type.next_element_type())
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+                Ok(format!("[{}]", elements.join(", ")))
+            }
+            // Literals, structs and arrays may also be given in Aleo syntax
+            (_, JsonValue::String(string)) => Ok(string.clone()),
+            _ => Err(format!("{input} is not a valid {plaintext_type}")),
+        }
+    }
+
+    fn check_value(&self, value: &Value<N>, value_type: &ValueType<N>) -> Result<(), String> {
+        match (value_type, value) {
+            (
+                ValueType::Constant(plaintext_type)
+                | ValueType::Public(plaintext_type)
+                | ValueType::Private(plaintext_type),
+                Value::Plaintext(plaintext),
+            ) => Self::check_plaintext(self.program, plaintext, plaintext_type),
+            (ValueType::Record(record_name), Value::Record(record)) => {
+                self.check_record(record, record_name)
+            }
+            // The types of external records are declared by the programs imported
+            (ValueType::ExternalRecord(..), Value::Record(..)) => Ok(()),
+            _ => Err(format!("expected a {value_type}, found {value}")),
+        }
+    }
+
+    // Check a plaintext has the given type, whose structs are declared by the given program
+    pub(super) fn check_plaintext(
+        program: &Program<N>,
+        plaintext: &Plaintext<N>,
+        plaintext_type: &PlaintextType<N>,
+    ) -> Result<(), String> {
+        match (plaintext_type, plaintext) {
+            (PlaintextType::Literal(literal_type), Plaintext::Literal(literal, _)) => {
+                match literal.to_type() == *literal_type {
+                    true => Ok(()),
+                    false => Err(format!("expected a {literal_type}, found {literal}")),
+                }
+            }
+            (PlaintextType::Struct(struct_name), Plaintext::Struct(members, _)) => {
+                let struct_type = program
+                    .get_struct(struct_name)
+                    .map_err(|error| error.to_string())?;
+                if members.len() != struct_type.members().len() {
+                    return Err(format!(
+                        "struct {struct_name} has {} members, found {}",
+                        struct_type.members().len(),
+                        members.len()
+                    ));
+                }
+                for (member_name, member_type) in struct_type.members() {
+                    let member = members
+                        .get(member_name)
+                        .ok_or_else(|| format!("member {member_name} is missing"))?;
+                    Self::check_plaintext(program, member, member_type)
+                        .map_err(|error| format!("{member_name}: {error}"))?;
+                }
+                Ok(())
+            }
+            (PlaintextType::Array(array_type), Plaintext::Array(elements, _)) => {
+                let length = **array_type.length() as usize;
+                if elements.len() != length {
+                    return Err(format!(
+                        "expected {length} elements, found {}",
+                        elements.len()
+                    ));
+                }
+                elements
+                    .iter()
+                    .enumerate()
+                    .try_for_each(|(index, element)| {
+                        Self::check_plaintext(program, element, array_type.next_element_type())
+                            .map_err(|error| format!("[{index}]: {error}"))
+                    })
+            }
+            _ => Err(format!("expected a {plaintext_type}, found {plaintext}")),
+        }
+    }
+
+    fn check_record(
+        &self,
+        record: &Record<N, Plaintext<N>>,
+        record_name: &Identifier<N>,
+    ) -> Result<(), String> {
+        let record_type = self
+            .program
+            .get_record(record_name)
+            .map_err(|error| error.to_string())?;
+        if record.owner().is_public() != record_type.owner().is_public() {
+            let visibility = match record_type.owner().is_public() {
+                true => "public",
+                false => "private",
+            };
+            return Err(format!(
+                "the owner of a {record_name} record must be {visibility}"
+            ));
+        }
+        if record.data().len() != record_type.entries().len() {
+            return Err(format!(
+                "a {record_name} record has {} entries, found {}",
+                record_type.entries().len(),
+                record.data().len()
+            ));
+        }
+        for (entry_name, entry_type) in record_type.entries() {
+            let entry = record
+                .data()
+                .get(entry_name)
+                .ok_or_else(|| format!("entry {entry_name} is missing"))?;
+            match (entry_type, entry) {
+                (EntryType::Constant(plaintext_type), Entry::Constant(plaintext))
+                | (EntryType::Public(plaintext_type), Entry::Public(plaintext))
+                | (EntryType::Private(plaintext_type), Entry::Private(plaintext)) => {
+                    Self::check_plaintext(self.program, plaintext, plaintext_type)
+                        .map_err(|error| format!("{entry_name}: {error}"))?
+                }
+                _ => return Err(format!("entry {entry_name} must be {entry_type}")),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network> ProgramManager<N> {
+    /// Coerce string arguments into values of the inputs of a program function, reporting every
+    /// argument which does not match the function's signature
+    pub fn validate_inputs(
+        &self,
+        program_id: impl TryInto<ProgramID<N>>,
+        function: impl TryInto<Identifier<N>>,
+        inputs: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> AvailResult<Vec<Value<N>>> {
+        let program_id = program_id
+            .try_into()
+            .map_err(|_| anyhow!("Invalid program ID"))?;
+        let program = match self.contains_program(program_id)? {
+            true => self.get_program(program_id)?,
+            false => self.find_program(&program_id)?,
+        };
+        Ok(InputValidator::new(&program, function)?.validate_strings(inputs)?)
+    }
+}
+
+// Check a string is a number without a literal type, e.g. `5` or `-5`
+fn is_bare_number(string: &str) -> bool {
+    let digits = string.strip_prefix('-').unwrap_or(string);
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const VALIDATION_PROGRAM: &str = "program validation_test.aleo;
+
+struct point:
+    x as u32;
+    y as u32;
+
+record token:
+    owner as address.private;
+    amount as u64.private;
+
+function move_point:
+    input r0 as point.public;
+    input r1 as u8.private;
+    input r2 as token.record;
+    output r0 as point.public;
+";
+
+    fn token(amount_visibility: &str) -> String {
+        format!(
+            "{{ owner: aleo1j7qxyunfldj2lp8hsvy7mw5k8zaqgjfyr72x2gh3x4ewgae8v5gscf5jh3.private, amount: 5u64.{amount_visibility}, _nonce: 440655410641037118713377218645355605135385337348439127168929531052605977026group.public }}"
+        )
+    }
+
+    #[test]
+    fn test_inputs_are_coerced_into_declared_types() {
+        let program = Program::<Testnet3>::from_str(VALIDATION_PROGRAM).unwrap();
+        let validator = InputValidator::new(&program, "move_point").unwrap();
+
+        let values = validator
+            .validate_strings(["{ x: 1u32, y: 2u32 }", " 7 ", token("private").as_str()])
+            .unwrap();
+        assert_eq!(values[1], Value::from_str("7u8").unwrap());
+
+        let json_values = validator
+            .validate_json([json!({"x": 1, "y": 2}), json!("7"), json!(token("private"))])
+            .unwrap();
+        assert_eq!(json_values, values);
+
+        // Ensure numbers given as strings take their type inside structs too
+        let json_values = validator
+            .validate_json([json!({"x": "1", "y": 2}), json!(7), json!(token("private"))])
+            .unwrap();
+        assert_eq!(json_values, values);
+        assert_eq!(validator.validate(values.clone()).unwrap(), values);
+    }
+
+    #[test]
+    fn test_every_invalid_argument_is_reported() {
+        let program = Program::<Testnet3>::from_str(VALIDATION_PROGRAM).unwrap();
+        let validator = InputValidator::new(&program, "move_point").unwrap();
+
+        let error = validator
+            .validate_strings(["{ x: 1u32 }", "300", token("public").as_str()])
+            .unwrap_err();
+        let indices = error
+            .errors
+            .iter()
+            .map(|error| error.index)
+            .collect::<Vec<_>>();
+        assert_eq!(indices, [0, 1, 2]);
+        assert_eq!(error.errors[1].expected, "u8.private");
+        assert!(error.errors[2].message.contains("amount"));
+
+        let error = validator
+            .validate_json([json!({"x": 1, "y": 2, "z": 3})])
+            .unwrap_err();
+        let messages = error
+            .errors
+            .iter()
+            .map(|error| (error.index, error.message.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            [
+                (0, "struct point has no member z"),
+                (1, "the argument is missing"),
+                (2, "the argument is missing")
+            ]
+        );
+
+        let error = AvailError::from(error);
+        assert_eq!(error.error_type, AvailErrorType::Validation);
+    }
+}
@@ -0,0 +1,471 @@
+use super::*;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value as JsonValue};
+
+/// The visibility of a value
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    Constant,
+    Public,
+    Private,
+}
+
+/// The type of a value
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TypeInterface {
+    /// A literal, named like in Aleo instructions, e.g. `u64` or `address`
+    Literal { name: String },
+    /// A struct of the program
+    Struct { name: String },
+    Array {
+        element: Box<TypeInterface>,
+        length: u32,
+    },
+    /// A record of the program
+    Record { name: String },
+    /// A record of an imported program
+    ExternalRecord { program_id: String, name: String },
+    /// The future of a call to the finalize block of a function
+    Future {
+        program_id: String,
+        function: String,
+    },
+}
+
+/// An input or output of a function. Records and futures have no visibility.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParameterInterface {
+    #[serde(rename = "type")]
+    pub value_type: TypeInterface,
+    pub visibility: Option<Visibility>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionInterface {
+    pub name: String,
+    pub inputs: Vec<ParameterInterface>,
+    pub outputs: Vec<ParameterInterface>,
+    /// Whether the function has a finalize block updating the state of the program on chain
+    pub has_finalize: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemberInterface {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub value_type: TypeInterface,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StructInterface {
+    pub name: String,
+    pub members: Vec<MemberInterface>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryInterface {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub value_type: TypeInterface,
+    pub visibility: Visibility,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordInterface {
+    pub name: String,
+    pub owner_visibility: Visibility,
+    pub entries: Vec<EntryInterface>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MappingInterface {
+    pub name: String,
+    pub key: TypeInterface,
+    pub value: TypeInterface,
+}
+
+/// A description of the interface of a program: its functions with their inputs and outputs,
+/// and the structs, records and mappings it declares.
+///
+/// The interface is serializable, and JSON Schemas of the inputs of its functions can be
+/// generated from it with [`ProgramInterface::input_schema`], e.g. to generate forms or validate
+/// requests.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgramInterface {
+    pub program_id: String,
+    pub imports: Vec<String>,
+    pub functions: Vec<FunctionInterface>,
+    pub structs: Vec<StructInterface>,
+    pub records: Vec<RecordInterface>,
+    pub mappings: Vec<MappingInterface>,
+}
+
+impl ProgramInterface {
+    /// Describe the interface of a program
+    pub fn from_program<N: Network>(program: &Program<N>) -> Self {
+        let functions = program
+            .functions()
+            .values()
+            .map(|function| FunctionInterface {
+                name: function.name().to_string(),
+                inputs: function
+                    .inputs()
+                    .iter()
+                    .map(|input| Self::parameter(input.value_type()))
+                    .collect(),
+                outputs: function
+                    .outputs()
+                    .iter()
+                    .map(|output| Self::parameter(output.value_type()))
+                    .collect(),
+                has_finalize: function.finalize_logic().is_some(),
+            })
+            .collect();
+        let structs = program
+            .structs()
+            .iter()
+            .map(|(name, struct_type)| StructInterface {
+                name: name.to_string(),
+                members: struct_type
+                    .members()
+                    .iter()
+                    .map(|(name, member_type)| MemberInterface {
+                        name: name.to_string(),
+                        value_type: Self::plaintext_type(member_type),
+                    })
+                    .collect(),
+            })
+            .collect();
+        let records = program
+            .records()
+            .iter()
+            .map(|(name, record_type)| RecordInterface {
+                name: name.to_string(),
+                owner_visibility: match record_type.owner().is_public() {
+                    true => Visibility::Public,
+                    false => Visibility::Private,
+                },
+                entries: record_type
+                    .entries()
+                    .iter()
+                    .map(|(name, entry_type)| {
+                        let (plaintext_type, visibility) = match entry_type {
+                            EntryType::Constant(plaintext_type) => {
+                                (plaintext_type, Visibility::Constant)
+                            }
+                            EntryType::Public(plaintext_type) => {
+                                (plaintext_type, Visibility::Public)
+                            }
+                            EntryType::Private(plaintext_type) => {
+                                (plaintext_type, Visibility::Private)
+                            }
+                        };
+                        EntryInterface {
+                            name: name.to_string(),
+                            value_type: Self::plaintext_type(plaintext_type),
+                            visibility,
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+        let mappings = program
+            .mappings()
+            .iter()
+            .map(|(name, mapping)| MappingInterface {
+                name: name.to_string(),
+                key: Self::plaintext_type(mapping.key().plaintext_type()),
+                value: Self::plaintext_type(mapping.value().plaintext_type()),
+            })
+            .collect();
+
+        Self {
+            program_id: program.id().to_string(),
+            imports: program.imports().keys().map(ToString::to_string).collect(),
+            functions,
+            structs,
+            records,
+            mappings,
+        }
+    }
+
+    /// Get a function of the program
+    pub fn function(&self, name: &str) -> Option<&FunctionInterface> {
+        self.functions.iter().find(|function| function.name == name)
+    }
+
+    /// Generate the JSON Schema of the inputs of a function, as an array with one item per input.
+    /// The schema accepts the JSON values [`InputValidator::validate_json`] coerces into inputs:
+    /// literals as numbers, booleans or strings, structs as objects, arrays as arrays and records
+    /// as strings.
+    pub fn input_schema(&self, function_name: &str) -> Result<JsonValue> {
+        let function = self.function(function_name).ok_or_else(|| {
+            anyhow!(
+                "Program {} does not contain function {function_name}",
+                self.program_id
+            )
+        })?;
+        let items = function
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(index, input)| {
+                let mut schema = self.type_schema(&input.value_type)?;
+                let visibility = match input.visibility {
+                    Some(Visibility::Constant) => ".constant",
+                    Some(Visibility::Public) => ".public",
+                    Some(Visibility::Private) => ".private",
+                    None => "",
+                };
+                schema["title"] = json!(format!("r{index}"));
+                schema["description"] = json!(format!(
+                    "{}{visibility}",
+                    Self::type_name(&input.value_type)
+                ));
+                Ok(schema)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": format!("{}/{}", self.program_id, function.name),
+            "type": "array",
+            "prefixItems": items,
+            "items": false,
+            "minItems": function.inputs.len(),
+            "maxItems": function.inputs.len(),
+        }))
+    }
+
+    fn type_schema(&self, value_type: &TypeInterface) -> Result<JsonValue> {
+        let schema = match value_type {
+            TypeInterface::Literal { name } => Self::literal_schema(name),
+            TypeInterface::Struct { name } => {
+                let struct_type = self
+                    .structs
+                    .iter()
+                    .find(|struct_type| struct_type.name == *name)
+                    .ok_or_else(|| anyhow!("Struct {name} is not declared by the program"))?;
+                let required = struct_type
+                    .members
+                    .iter()
+                    .map(|member| &member.name)
+                    .collect::<Vec<_>>();
+                let mut properties = Map::new();
+                for member in &struct_type.members {
+                    properties.insert(member.name.clone(), self.type_schema(&member.value_type)?);
+                }
+                json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                    "additionalProperties": false,
+                })
+            }
+            TypeInterface::Array { element, length } => json!({
+                "type": "array",
+                "items": self.type_schema(element)?,
+                "minItems": length,
+                "maxItems": length,
+            }),
+            TypeInterface::Record { .. } | TypeInterface::ExternalRecord { .. } => json!({
+                "type": "string",
+                "pattern": "^\\s*\\{[\\s\\S]*\\}\\s*$",
+            }),
+            TypeInterface::Future { .. } => bail!("Futures cannot be given as inputs"),
+        };
+        Ok(schema)
+    }
+
+    fn literal_schema(name: &str) -> JsonValue {
+        // Integers are given as numbers or strings, with or without their type. The bounds only
+        // apply to numbers, and are left out where they do not fit in a double.
+        let integer = |pattern: String, minimum: Option<i64>, maximum: Option<i64>| {
+            let mut schema = json!({ "type": ["integer", "string"], "pattern": pattern });
+            if let Some(minimum) = minimum {
+                schema["minimum"] = minimum.into();
+            }
+            if let Some(maximum) = maximum {
+                schema["maximum"] = maximum.into();
+            }
+            schema
+        };
+        let unsigned = format!("^[0-9]+({name})?$");
+        let signed = format!("^-?[0-9]+({name})?$");
+        match name {
+            "boolean" => json!({ "type": "boolean" }),
+            "u8" => integer(unsigned, Some(0), Some(u8::MAX.into())),
+            "u16" => integer(unsigned, Some(0), Some(u16::MAX.into())),
+            "u32" => integer(unsigned, Some(0), Some(u32::MAX.into())),
+            "u64" | "u128" => integer(unsigned, Some(0), None),
+            "i8" => integer(signed, Some(i8::MIN.into()), Some(i8::MAX.into())),
+            "i16" => integer(signed, Some(i16::MIN.into()), Some(i16::MAX.into())),
+            "i32" => integer(signed, Some(i32::MIN.into()), Some(i32::MAX.into())),
+            "i64" | "i128" => integer(signed, None, None),
+            "field" | "scalar" => {
+                json!({ "type": "string", "pattern": format!("^[0-9]+({name})?$") })
+            }
+            "group" => json!({ "type": "string", "pattern": "^-?[0-9]+(group)?$" }),
+            "address" => json!({ "type": "string", "pattern": "^aleo1[a-z0-9]{58}$" }),
+            "signature" => json!({ "type": "string", "pattern": "^sign1[a-z0-9]+$" }),
+            _ => json!({ "type": "string" }),
+        }
+    }
+
+    fn type_name(value_type: &TypeInterface) -> String {
+        match value_type {
+            TypeInterface::Literal { name } | TypeInterface::Struct { name } => name.clone(),
+            TypeInterface::Array { element, length } => {
+                format!("[{}; {length}u32]", Self::type_name(element))
+            }
+            TypeInterface::Record { name } => format!("{name}.record"),
+            TypeInterface::ExternalRecord { program_id, name } => {
+                format!("{program_id}/{name}.record")
+            }
+            TypeInterface::Future {
+                program_id,
+                function,
+            } => format!("{program_id}/{function}.future"),
+        }
+    }
+
+    fn parameter<N: Network>(value_type: &ValueType<N>) -> ParameterInterface {
+        let (value_type, visibility) = match value_type {
+            ValueType::Constant(plaintext_type) => (
+                Self::plaintext_type(plaintext_type),
+                Some(Visibility::Constant),
+            ),
+            ValueType::Public(plaintext_type) => (
+                Self::plaintext_type(plaintext_type),
+                Some(Visibility::Public),
+            ),
+            ValueType::Private(plaintext_type) => (
+                Self::plaintext_type(plaintext_type),
+                Some(Visibility::Private),
+            ),
+            ValueType::Record(name) => (
+                TypeInterface::Record {
+                    name: name.to_string(),
+                },
+                None,
+            ),
+            ValueType::ExternalRecord(locator) => (
+                TypeInterface::ExternalRecord {
+                    program_id: locator.program_id().to_string(),
+                    name: locator.resource().to_string(),
+                },
+                None,
+            ),
+            ValueType::Future(locator) => (
+                TypeInterface::Future {
+                    program_id: locator.program_id().to_string(),
+                    function: locator.resource().to_string(),
+                },
+                None,
+            ),
+        };
+        ParameterInterface {
+            value_type,
+            visibility,
+        }
+    }
+
+    fn plaintext_type<N: Network>(plaintext_type: &PlaintextType<N>) -> TypeInterface {
+        match plaintext_type {
+            PlaintextType::Literal(literal_type) => TypeInterface::Literal {
+                name: literal_type.to_string(),
+            },
+            PlaintextType::Struct(name) => TypeInterface::Struct {
+                name: name.to_string(),
+            },
+            PlaintextType::Array(array_type) => TypeInterface::Array {
+                element: Box::new(Self::plaintext_type(array_type.next_element_type())),
+                length: **array_type.length(),
+            },
+        }
+    }
+}
+
+impl<N: Network> ProgramManager<N> {
+    /// Describe the interface of a program, taken from the program manager if it was added to it,
+    /// otherwise from the local program directory or the network
+    pub fn get_program_interface(
+        &self,
+        program_id: impl TryInto<ProgramID<N>>,
+    ) -> Result<ProgramInterface> {
+        let program_id = program_id
+            .try_into()
+            .map_err(|_| anyhow!("Invalid program ID"))?;
+        let program = match self.contains_program(program_id)? {
+            true => self.get_program(program_id)?,
+            false => self.find_program(&program_id)?,
+        };
+        Ok(ProgramInterface::from_program(&program))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aleo_tools::test_utils::FEE_ESTIMATION_PROGRAM;
+
+    #[test]
+    fn test_program_interfaces_describe_programs() {
+        let program = Program::<Testnet3>::from_str(FEE_ESTIMATION_PROGRAM).unwrap();
+        let interface = ProgramInterface::from_program(&program);
+        assert_eq!(interface.program_id, "feeestimation.aleo");
+        assert_eq!(interface.structs[0].members.len(), 2);
+        assert_eq!(
+            interface.mappings[0].value,
+            TypeInterface::Struct {
+                name: "function_size".to_string()
+            }
+        );
+
+        let function = interface.function("add_size_record").unwrap();
+        assert!(function.has_finalize);
+        assert_eq!(
+            function.inputs[2],
+            ParameterInterface {
+                value_type: TypeInterface::Literal {
+                    name: "u64".to_string()
+                },
+                visibility: Some(Visibility::Public),
+            }
+        );
+        assert_eq!(
+            function.outputs[0].value_type,
+            TypeInterface::Future {
+                program_id: "feeestimation.aleo".to_string(),
+                function: "add_size_record".to_string()
+            }
+        );
+
+        // Ensure the interface survives a round trip through JSON
+        let json = serde_json::to_string(&interface).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ProgramInterface>(&json).unwrap(),
+            interface
+        );
+    }
+
+    #[test]
+    fn test_input_schemas_describe_function_inputs() {
+        let program = Program::<Testnet3>::from_str(FEE_ESTIMATION_PROGRAM).unwrap();
+        let interface = ProgramInterface::from_program(&program);
+        let schema = interface.input_schema("main").unwrap();
+        assert_eq!(schema["minItems"], 2);
+        assert_eq!(
+            schema["prefixItems"][0]["type"],
+            json!(["integer", "string"])
+        );
+        assert_eq!(schema["prefixItems"][0]["pattern"], "^[0-9]+(u32)?$");
+        assert_eq!(schema["prefixItems"][0]["minimum"], 0);
+        assert_eq!(schema["prefixItems"][0]["maximum"], u32::MAX);
+        assert_eq!(schema["prefixItems"][1]["description"], "u32.private");
+        assert!(interface.input_schema("missing").is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use super::*;
+use crate::aleo_tools::APP_INFO;
+use app_dirs::AppDataType;
+use sha2::{Digest, Sha256};
+use std::{fs, marker::PhantomData, path::Path};
+
+/// Cache persisting the proving and verifying keys synthesized for program functions, so they
+/// are not synthesized again by every execution.
+///
+/// Keys are stored per network, program ID, program checksum and function. When a program
+/// changes, its checksum changes with it, so keys synthesized for a previous version are never
+/// loaded, and they are removed when the keys of the new version are stored. The keys of
+/// `credits.aleo` are shipped with snarkVM and are not cached.
+#[derive(Clone, Debug)]
+pub struct KeyCache<N: Network> {
+    directory: PathBuf,
+    _network: PhantomData<N>,
+}
+
+impl<N: Network> KeyCache<N> {
+    /// Create a cache storing keys in the given directory
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            _network: PhantomData,
+        }
+    }
+
+    /// Create a cache storing keys in the cache directory of the application
+    pub fn in_app_cache() -> Result<Self> {
+        let directory = app_dirs::app_dir(AppDataType::UserCache, &APP_INFO, "keys")
+            .map_err(|error| anyhow!("Failed to open the key cache directory: {error}"))?;
+        Ok(Self::new(directory))
+    }
+
+    /// Get the directory the keys are stored in
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Compute the checksum identifying a version of a program in the cache
+    pub fn checksum(program: &Program<N>) -> String {
+        hex::encode(Sha256::digest(program.to_string().as_bytes()))
+    }
+
+    /// Get the keys of a function of the given version of a program, if they are cached
+    pub fn load(
+        &self,
+        program: &Program<N>,
+        function_name: &Identifier<N>,
+    ) -> Result<Option<(ProvingKey<N>, VerifyingKey<N>)>> {
+        let (prover_path, verifier_path) = self.key_paths(program, function_name);
+        if !prover_path.exists() || !verifier_path.exists() {
+            return Ok(None);
+        }
+        let proving_key = ProvingKey::from_bytes_le(&fs::read(prover_path)?)?;
+        let verifying_key = VerifyingKey::from_bytes_le(&fs::read(verifier_path)?)?;
+        Ok(Some((proving_key, verifying_key)))
+    }
+
+    /// Store the keys of a function of the given version of a program, removing the keys of other
+    /// versions of the program
+    pub fn store(
+        &self,
+        program: &Program<N>,
+        function_name: &Identifier<N>,
+        proving_key: &ProvingKey<N>,
+        verifying_key: &VerifyingKey<N>,
+    ) -> Result<()> {
+        self.remove_other_versions(program)?;
+        let (prover_path, verifier_path) = self.key_paths(program, function_name);
+        if let Some(parent) = prover_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        Self::write(&prover_path, &proving_key.to_bytes_le()?)?;
+        Self::write(&verifier_path, &verifying_key.to_bytes_le()?)
+    }
+
+    /// Remove the cached keys of every version of a program
+    pub fn invalidate(&self, program_id: &ProgramID<N>) -> Result<()> {
+        let directory = self.program_directory(program_id);
+        if directory.exists() {
+            fs::remove_dir_all(directory)?;
+        }
+        Ok(())
+    }
+
+    /// Insert the cached keys of the functions of a program and its imports into a process which
+    /// contains them, returning the number of functions whose keys were loaded
+    pub fn load_into(&self, process: &Process<N>, program: &Program<N>) -> Result<usize> {
+        let mut loaded = 0;
+        for program in Self::programs_in(process, program)? {
+            for function_name in program.functions().keys() {
+                if process
+                    .get_proving_key(*program.id(), *function_name)
+                    .is_ok()
+                {
+                    continue;
+                }
+                if let Some((proving_key, verifying_key)) = self.load(&program, function_name)? {
+                    process.insert_proving_key(program.id(), function_name, proving_key)?;
+                    process.insert_verifying_key(program.id(), function_name, verifying_key)?;
+                    loaded += 1;
+                }
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Store the keys a process has synthesized for the functions of a program and its imports
+    /// which are not cached yet, returning the number of functions whose keys were stored
+    pub fn store_from(&self, process: &Process<N>, program: &Program<N>) -> Result<usize> {
+        let mut stored = 0;
+        for program in Self::programs_in(process, program)? {
+            for function_name in program.functions().keys() {
+                let (prover_path, verifier_path) = self.key_paths(&program, function_name);
+                if prover_path.exists() && verifier_path.exists() {
+                    continue;
+                }
+                let keys = (
+                    process.get_proving_key(*program.id(), *function_name),
+                    process.get_verifying_key(*program.id(), *function_name),
+                );
+                if let (Ok(proving_key), Ok(verifying_key)) = keys {
+                    self.store(&program, function_name, &proving_key, &verifying_key)?;
+                    stored += 1;
+                }
+            }
+        }
+        Ok(stored)
+    }
+
+    // Collect a program and its imports from the process, except `credits.aleo`
+    fn programs_in(process: &Process<N>, program: &Program<N>) -> Result<Vec<Program<N>>> {
+        let credits_id = ProgramID::<N>::from_str("credits.aleo")?;
+        let mut programs = vec![program.clone()];
+        let mut index = 0;
+        while index < programs.len() {
+            for import_id in programs[index].imports().keys() {
+                let is_collected = programs.iter().any(|program| program.id() == import_id);
+                if *import_id != credits_id && !is_collected {
+                    programs.push(process.get_program(*import_id)?.clone());
+                }
+            }
+            index += 1;
+        }
+        Ok(programs)
+    }
+
+    fn program_directory(&self, program_id: &ProgramID<N>) -> PathBuf {
+        self.directory
+            .join(N::ID.to_string())
+            .join(program_id.to_string())
+    }
+
+    fn key_paths(&self, program: &Program<N>, function_name: &Identifier<N>) -> (PathBuf, PathBuf) {
+        let directory = self
+            .program_directory(program.id())
+            .join(Self::checksum(program));
+        (
+            directory.join(format!("{function_name}.prover")),
+            directory.join(format!("{function_name}.verifier")),
+        )
+    }
+
+    fn remove_other_versions(&self, program: &Program<N>) -> Result<()> {
+        let directory = self.program_directory(program.id());
+        if !directory.exists() {
+            return Ok(());
+        }
+        let checksum = Self::checksum(program);
+        for entry in fs::read_dir(directory)? {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy() != checksum {
+                fs::remove_dir_all(entry.path())?;
+            }
+        }
+        Ok(())
+    }
+
+    // Keys are written through a temporary file, so an interrupted write never leaves a truncated
+    // key behind
+    fn write(path: &Path, bytes: &[u8]) -> Result<()> {
+        let temporary_path = path.with_extension("tmp");
+        fs::write(&temporary_path, bytes)?;
+        fs::rename(temporary_path, path)?;
+        Ok(())
+    }
+}
+
+impl<N: Network> ProgramManager<N> {
+    /// Use a key cache, so the keys of the programs executed are synthesized once and then
+    /// loaded from disk
+    pub fn with_key_cache(mut self, key_cache: KeyCache<N>) -> Self {
+        self.key_cache = Some(key_cache);
+        self
+    }
+
+    /// Get the key cache used by the program manager, if any
+    pub fn key_cache(&self) -> Option<&KeyCache<N>> {
+        self.key_cache.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aleo_tools::test_utils::{HELLO_PROGRAM, HELLO_PROGRAM_2};
+    use snarkvm::circuit::AleoV0;
+
+    #[test]
+    fn test_keys_are_cached_per_program_version() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache = KeyCache::<Testnet3>::new(directory.path());
+        let program = Program::<Testnet3>::from_str(HELLO_PROGRAM).unwrap();
+        let function_name = Identifier::from_str("hello").unwrap();
+
+        // Synthesize the keys of the program and store them
+        let mut process = Process::<Testnet3>::load().unwrap();
+        process.add_program(&program).unwrap();
+        assert_eq!(cache.store_from(&process, &program).unwrap(), 0);
+        process
+            .synthesize_key::<AleoV0, _>(program.id(), &function_name, &mut TestRng::default())
+            .unwrap();
+        assert_eq!(cache.store_from(&process, &program).unwrap(), 1);
+
+        // Ensure the keys are loaded into a fresh process
+        let mut fresh_process = Process::<Testnet3>::load().unwrap();
+        fresh_process.add_program(&program).unwrap();
+        assert_eq!(cache.load_into(&fresh_process, &program).unwrap(), 1);
+        assert_eq!(
+            fresh_process
+                .get_verifying_key(*program.id(), function_name)
+                .unwrap(),
+            process
+                .get_verifying_key(*program.id(), function_name)
+                .unwrap()
+        );
+
+        // Ensure keys of a previous version of the program are not loaded, and removed once keys
+        // of the new version are stored
+        let updated_program = Program::<Testnet3>::from_str(HELLO_PROGRAM_2).unwrap();
+        assert_eq!(updated_program.id(), program.id());
+        assert!(cache
+            .load(&updated_program, &function_name)
+            .unwrap()
+            .is_none());
+        let (proving_key, verifying_key) = cache.load(&program, &function_name).unwrap().unwrap();
+        cache
+            .store(
+                &updated_program,
+                &function_name,
+                &proving_key,
+                &verifying_key,
+            )
+            .unwrap();
+        assert!(cache.load(&program, &function_name).unwrap().is_none());
+
+        cache.invalidate(program.id()).unwrap();
+        assert!(cache
+            .load(&updated_program, &function_name)
+            .unwrap()
+            .is_none());
+    }
+}
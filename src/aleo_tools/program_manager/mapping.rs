@@ -0,0 +1,289 @@
+use super::*;
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Number, Value as JsonValue};
+
+impl<N: Network> ProgramManager<N> {
+    /// Read the value stored under a key of an on-chain mapping, or `None` if the key is not in
+    /// the mapping. The key and the value are checked against the types the deployed program
+    /// declares for the mapping.
+    pub fn read_mapping_value(
+        &self,
+        program_id: impl TryInto<ProgramID<N>>,
+        mapping_name: impl TryInto<Identifier<N>>,
+        key: &Plaintext<N>,
+    ) -> Result<Option<Plaintext<N>>> {
+        let (program, mapping) = self.find_mapping(program_id, mapping_name)?;
+        self.read_checked_value(&program, &mapping, key)
+    }
+
+    /// Read the value stored under a key of an on-chain mapping and decode it into a Rust type,
+    /// or `None` if the key is not in the mapping.
+    ///
+    /// The key is serialized with serde and encoded into the key type declared by the mapping:
+    /// numbers take the suffix of their literal type, objects become structs and sequences become
+    /// arrays, while strings are parsed in Aleo syntax (e.g. addresses or `1field`). The value is
+    /// decoded from JSON in which integers up to 64 bits and booleans are numbers and booleans,
+    /// 128 bit integers are numbers when serde_json can represent them and strings otherwise,
+    /// other literals are strings in Aleo syntax, structs are objects and arrays are sequences.
+    pub fn read_mapping<T: DeserializeOwned>(
+        &self,
+        program_id: impl TryInto<ProgramID<N>>,
+        mapping_name: impl TryInto<Identifier<N>>,
+        key: &impl Serialize,
+    ) -> Result<Option<T>> {
+        let (program, mapping) = self.find_mapping(program_id, mapping_name)?;
+        let key_type = mapping.key().plaintext_type();
+        let key = serde_json::to_value(key)?;
+        let key = InputValidator::json_to_plaintext(&program, &key, key_type)
+            .map_err(|error| anyhow!("❌ Invalid key for mapping {}: {error}", mapping.name()))?;
+        let key = Plaintext::<N>::from_str(&key)
+            .map_err(|error| anyhow!("❌ Invalid key for mapping {}: {error}", mapping.name()))?;
+
+        match self.read_checked_value(&program, &mapping, &key)? {
+            Some(value) => {
+                let value = plaintext_to_json(&value)?;
+                serde_json::from_value(value).map(Some).map_err(|error| {
+                    anyhow!(
+                        "❌ Failed to decode the value of mapping {}: {error}",
+                        mapping.name()
+                    )
+                })
+            }
+            None => Ok(None),
+        }
+    }
+
+    // Find a mapping declared by a program deployed on chain
+    fn find_mapping(
+        &self,
+        program_id: impl TryInto<ProgramID<N>>,
+        mapping_name: impl TryInto<Identifier<N>>,
+    ) -> Result<(Program<N>, Mapping<N>)> {
+        let program_id = program_id
+            .try_into()
+            .map_err(|_| anyhow!("Invalid program ID"))?;
+        let mapping_name = mapping_name
+            .try_into()
+            .map_err(|_| anyhow!("Invalid mapping name"))?;
+        let program = self.api_client()?.get_program(program_id)?;
+        let mapping = program.get_mapping(&mapping_name).map_err(|_| {
+            anyhow!("❌ Program {program_id:?} does not declare a mapping {mapping_name}")
+        })?;
+        Ok((program, mapping))
+    }
+
+    // Read the value under a key of a mapping, checking the key and the value against the types
+    // declared by the mapping
+    fn read_checked_value(
+        &self,
+        program: &Program<N>,
+        mapping: &Mapping<N>,
+        key: &Plaintext<N>,
+    ) -> Result<Option<Plaintext<N>>> {
+        let mapping_name = mapping.name();
+        InputValidator::check_plaintext(program, key, mapping.key().plaintext_type())
+            .map_err(|error| anyhow!("❌ Invalid key for mapping {mapping_name}: {error}"))?;
+
+        let value = self
+            .api_client()?
+            .find_mapping_value(*program.id(), *mapping_name, key)?;
+        match value {
+            Some(Value::Plaintext(value)) => {
+                InputValidator::check_plaintext(program, &value, mapping.value().plaintext_type())
+                    .map_err(|error| {
+                        anyhow!("❌ Unexpected value in mapping {mapping_name}: {error}")
+                    })?;
+                Ok(Some(value))
+            }
+            Some(value) => bail!("❌ Unexpected value in mapping {mapping_name}: {value}"),
+            None => Ok(None),
+        }
+    }
+}
+
+// Convert a plaintext into the JSON its Rust type is decoded from
+fn plaintext_to_json<N: Network>(plaintext: &Plaintext<N>) -> Result<JsonValue> {
+    match plaintext {
+        Plaintext::Literal(literal, _) => {
+            let literal_type = literal.to_type();
+            let string = literal.to_string();
+            let number = string.strip_suffix(&literal_type.to_string());
+            Ok(match (literal_type, number) {
+                (LiteralType::Boolean, _) => JsonValue::Bool(string == "true"),
+                (
+                    LiteralType::U8 | LiteralType::U16 | LiteralType::U32 | LiteralType::U64,
+                    Some(number),
+                ) => JsonValue::Number(Number::from(number.parse::<u64>()?)),
+                (
+                    LiteralType::I8 | LiteralType::I16 | LiteralType::I32 | LiteralType::I64,
+                    Some(number),
+                ) => JsonValue::Number(Number::from(number.parse::<i64>()?)),
+                (LiteralType::U128, Some(number)) => match number.parse::<u64>() {
+                    Ok(number) => JsonValue::Number(Number::from(number)),
+                    Err(_) => JsonValue::String(number.to_string()),
+                },
+                (LiteralType::I128, Some(number)) => match number.parse::<i64>() {
+                    Ok(number) => JsonValue::Number(Number::from(number)),
+                    Err(_) => JsonValue::String(number.to_string()),
+                },
+                (LiteralType::String, _) => JsonValue::String(string.trim_matches('"').to_string()),
+                _ => JsonValue::String(string),
+            })
+        }
+        Plaintext::Struct(members, _) => Ok(JsonValue::Object(
+            members
+                .iter()
+                .map(|(name, member)| Ok((name.to_string(), plaintext_to_json(member)?)))
+                .collect::<Result<Map<_, _>>>()?,
+        )),
+        Plaintext::Array(elements, _) => Ok(JsonValue::Array(
+            elements
+                .iter()
+                .map(plaintext_to_json)
+                .collect::<Result<Vec<_>>>()?,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aleo_tools::test_utils::{MockLedger, MockNodeServer, FEE_ESTIMATION_PROGRAM};
+    use crate::models::constants::TESTNET_PRIVATE_KEY;
+
+    use serde::Deserialize;
+
+    const STRUCT_KEY_PROGRAM: &str = "program struct_key.aleo;
+
+struct pair:
+    first as u32;
+    second as u32;
+
+mapping totals:
+    key as pair.public;
+    value as u64.public;
+
+function main:
+    input r0 as u32.public;
+    output r0 as u32.public;
+";
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct FunctionSize {
+        function_id: String,
+        size: u64,
+    }
+
+    #[derive(Serialize)]
+    struct Pair {
+        first: u32,
+        second: u32,
+    }
+
+    #[test]
+    fn test_mapping_reads_are_typed() {
+        let private_key = PrivateKey::<Testnet3>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_program(Program::from_str(FEE_ESTIMATION_PROGRAM).unwrap());
+        ledger
+            .set_mapping_value(
+                "feeestimation.aleo",
+                "size_records",
+                "1field",
+                Value::from_str("{ function_id: 2field, size: 3u64 }").unwrap(),
+            )
+            .unwrap();
+        let program_manager =
+            ProgramManager::<Testnet3>::new(Some(private_key), None, Some(ledger.client()), None)
+                .unwrap();
+
+        let size = program_manager
+            .read_mapping::<FunctionSize>("feeestimation.aleo", "size_records", &"1field")
+            .unwrap();
+        assert_eq!(
+            size,
+            Some(FunctionSize {
+                function_id: "2field".to_string(),
+                size: 3,
+            })
+        );
+        let size = program_manager
+            .read_mapping_value(
+                "feeestimation.aleo",
+                "size_records",
+                &Plaintext::from_str("1field").unwrap(),
+            )
+            .unwrap();
+        assert_eq!(
+            size,
+            Some(Plaintext::from_str("{ function_id: 2field, size: 3u64 }").unwrap())
+        );
+
+        // Ensure numbers take the suffix of the key type, and missing keys are read as `None`
+        let size = program_manager
+            .read_mapping::<FunctionSize>("feeestimation.aleo", "size_records", &1)
+            .unwrap();
+        assert_eq!(size.map(|size| size.size), Some(3));
+        let size = program_manager
+            .read_mapping::<FunctionSize>("feeestimation.aleo", "size_records", &5)
+            .unwrap();
+        assert_eq!(size, None);
+
+        // Ensure keys of the wrong type and unknown mappings are rejected
+        assert!(program_manager
+            .read_mapping::<FunctionSize>("feeestimation.aleo", "size_records", &true)
+            .is_err());
+        assert!(program_manager
+            .read_mapping_value(
+                "feeestimation.aleo",
+                "size_records",
+                &Plaintext::from_str("1u64").unwrap(),
+            )
+            .is_err());
+        assert!(program_manager
+            .read_mapping::<FunctionSize>("feeestimation.aleo", "sizes", &"1field")
+            .is_err());
+
+        // Ensure struct keys are encoded in the request path, over HTTP
+        ledger.add_program(Program::from_str(STRUCT_KEY_PROGRAM).unwrap());
+        ledger
+            .set_mapping_value(
+                "struct_key.aleo",
+                "totals",
+                "{ first: 1u32, second: 2u32 }",
+                Value::from_str("7u64").unwrap(),
+            )
+            .unwrap();
+        let server = MockNodeServer::start(ledger).unwrap();
+        let program_manager = ProgramManager::<Testnet3>::new(
+            Some(private_key),
+            None,
+            Some(server.client("testnet3")),
+            None,
+        )
+        .unwrap();
+        let total = program_manager
+            .read_mapping::<u64>(
+                "struct_key.aleo",
+                "totals",
+                &Pair {
+                    first: 1,
+                    second: 2,
+                },
+            )
+            .unwrap();
+        assert_eq!(total, Some(7));
+        let total = program_manager
+            .read_mapping::<u64>(
+                "struct_key.aleo",
+                "totals",
+                &Pair {
+                    first: 2,
+                    second: 1,
+                },
+            )
+            .unwrap();
+        assert_eq!(total, None);
+    }
+}
@@ -95,7 +95,7 @@ mod tests {
     use super::*;
     use crate::aleo_tools::{
         api::AleoAPIClient,
-        test_utils::{random_program, GENERIC_PROGRAM_BODY},
+        test_utils::{random_program, MockLedger, GENERIC_PROGRAM_BODY},
     };
     use crate::models::constants::TESTNET_PRIVATE_KEY;
     use snarkvm::console::{account::PrivateKey, network::Testnet3};
@@ -153,4 +153,45 @@ mod tests {
 
         let _ = std::fs::remove_dir_all(temp_dir);
     }
+
+    #[test]
+    fn test_network_functionality_offline() {
+        let credits = snarkvm::synthesizer::Program::<Testnet3>::credits().unwrap();
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_program(credits.clone());
+        let private_key = PrivateKey::<Testnet3>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+
+        let program_manager =
+            ProgramManager::<Testnet3>::new(Some(private_key), None, Some(ledger.client()), None)
+                .unwrap();
+
+        // Test that the program manager reads programs from the ledger
+        let program_state = program_manager.on_chain_program_state(&credits).unwrap();
+        assert!(matches!(program_state, OnChainProgramState::Same));
+
+        let random_program = random_program();
+        let random_program_state = program_manager
+            .on_chain_program_state(&random_program)
+            .unwrap();
+        assert!(matches!(
+            random_program_state,
+            OnChainProgramState::NotDeployed
+        ));
+
+        let wrong_credits_program_string =
+            String::from("program credits.aleo;\n").add(GENERIC_PROGRAM_BODY);
+        let wrong_credits_program =
+            Program::<Testnet3>::from_str(&wrong_credits_program_string).unwrap();
+        let state_mismatch = program_manager
+            .on_chain_program_state(&wrong_credits_program)
+            .unwrap();
+        assert!(matches!(state_mismatch, OnChainProgramState::Different));
+
+        // Test that newly deployed programs become visible
+        ledger.add_program(random_program.clone());
+        let random_program_state = program_manager
+            .on_chain_program_state(&random_program)
+            .unwrap();
+        assert!(matches!(random_program_state, OnChainProgramState::Same));
+    }
 }
@@ -0,0 +1,273 @@
+use super::*;
+use crate::aleo_tools::coin_selection::{select_coins, Coin, SelectionStrategy};
+use crate::models::encrypted_data::EventTypeCommon;
+use snarkvm::ledger::{block::*, query::*};
+
+/// Microcredits deducted by `credits.aleo/split` from the record being split
+pub const SPLIT_FEE: u64 = 10_000;
+
+/// A record consumed by a planned operation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlannedRecord<T> {
+    /// A record the wallet already owns
+    Existing(T),
+    /// An output record of an earlier operation of the plan. Joins have a single output, while
+    /// splits output the split off amount first and the remainder second.
+    Output { operation: usize, output: usize },
+}
+
+/// An operation on credits records
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecordOperation<T> {
+    /// Join two records into one holding their combined microcredits
+    Join(PlannedRecord<T>, PlannedRecord<T>),
+    /// Split the given amount of microcredits off a record
+    Split(PlannedRecord<T>, u64),
+}
+
+impl<T> RecordOperation<T> {
+    /// Get the type of the event recording the operation
+    pub fn event_type(&self) -> EventTypeCommon {
+        match self {
+            Self::Join(..) => EventTypeCommon::Join,
+            Self::Split(..) => EventTypeCommon::Split,
+        }
+    }
+}
+
+/// Joins and splits to perform, in order, so that a transfer can spend a single record for its
+/// amount and a single record for its fee
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordPlan<T> {
+    pub operations: Vec<RecordOperation<T>>,
+    /// The record to spend for the transfer amount once the operations are performed
+    pub amount_record: PlannedRecord<T>,
+    /// The record to spend for the transfer fee once the operations are performed, if any
+    pub fee_record: Option<PlannedRecord<T>>,
+}
+
+impl<T> RecordPlan<T> {
+    /// Whether the transfer can be made without joining or splitting records first
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+}
+
+impl<T: Coin + Clone> RecordPlan<T> {
+    /// Plan the fewest joins and splits of the given records needed before transferring `amount`
+    /// microcredits with a fee of `fee` microcredits paid by a record.
+    ///
+    /// Records are picked with the given coin-selection strategy. If the records cannot provide
+    /// two separate records for the amount and the fee, the largest records are joined and the
+    /// amount is split off the result. The fees of the planned joins are not included, they are
+    /// expected to be paid from the public balance.
+    pub fn new(records: &[T], amount: u64, fee: u64, strategy: SelectionStrategy) -> Result<Self> {
+        ensure!(amount > 0, "The transfer amount must be greater than 0");
+        let mut operations = vec![];
+
+        if let Ok(selection) = select_coins(records, amount, fee, strategy) {
+            let amount_record = Self::join_all(&mut operations, selection.amount_records);
+            let fee_record = match selection.fee_records.is_empty() {
+                true => None,
+                false => Some(Self::join_all(&mut operations, selection.fee_records)),
+            };
+            return Ok(Self {
+                operations,
+                amount_record,
+                fee_record,
+            });
+        }
+
+        // No two separate records can cover the transfer, so join enough records to cover the
+        // amount, the fee and the split fee, and split the amount off
+        ensure!(
+            fee > 0,
+            "Insufficient funds to transfer {amount} microcredits"
+        );
+        let required = amount as u128 + fee as u128 + SPLIT_FEE as u128;
+        let mut largest_first = records.to_vec();
+        largest_first.sort_by_key(|record| std::cmp::Reverse(record.value()));
+        let mut selected = vec![];
+        let mut total = 0u128;
+        for record in largest_first {
+            if total >= required {
+                break;
+            }
+            total += record.value() as u128;
+            selected.push(record);
+        }
+        ensure!(
+            total >= required,
+            "Insufficient funds: {total} microcredits available, {required} microcredits required to split off the transfer amount and fee"
+        );
+        let joined = Self::join_all(&mut operations, selected);
+        operations.push(RecordOperation::Split(joined, amount));
+        let split = operations.len() - 1;
+        Ok(Self {
+            operations,
+            amount_record: PlannedRecord::Output {
+                operation: split,
+                output: 0,
+            },
+            fee_record: Some(PlannedRecord::Output {
+                operation: split,
+                output: 1,
+            }),
+        })
+    }
+
+    // Plan joining the records one after the other, returning the record holding the sum
+    fn join_all(operations: &mut Vec<RecordOperation<T>>, records: Vec<T>) -> PlannedRecord<T> {
+        let mut records = records.into_iter();
+        let first = records.next().map(PlannedRecord::Existing);
+        records.fold(
+            first.expect("coin selection always selects a record"),
+            |joined, record| {
+                operations.push(RecordOperation::Join(
+                    joined,
+                    PlannedRecord::Existing(record),
+                ));
+                PlannedRecord::Output {
+                    operation: operations.len() - 1,
+                    output: 0,
+                }
+            },
+        )
+    }
+}
+
+impl<N: Network> ProgramManager<N> {
+    /// Join two credits records into a single record holding their combined microcredits. The fee
+    /// is paid with the fee record if one is specified, or from the public balance otherwise.
+    pub fn join(
+        &self,
+        first_record: Record<N, Plaintext<N>>,
+        second_record: Record<N, Plaintext<N>>,
+        priority_fee: u64,
+        fee_record: Option<Record<N, Plaintext<N>>>,
+        password: Option<&str>,
+    ) -> Result<N::TransactionID> {
+        let inputs = vec![Value::Record(first_record), Value::Record(second_record)];
+        let transaction =
+            self.create_credits_transaction("join", inputs, priority_fee, fee_record, password)?;
+
+        println!("Attempting to broadcast join transaction");
+        self.broadcast_transaction(transaction.clone())?;
+        Ok(transaction.id())
+    }
+
+    /// Split the given amount of microcredits off a credits record, producing one record with
+    /// the amount and one with the remainder. Splits need no fee record, the network deducts
+    /// [`SPLIT_FEE`] microcredits from the record being split.
+    pub fn split(
+        &self,
+        record: Record<N, Plaintext<N>>,
+        amount: u64,
+        password: Option<&str>,
+    ) -> Result<N::TransactionID> {
+        ensure!(
+            record.microcredits()? >= amount.saturating_add(SPLIT_FEE),
+            "Credits in the record must cover the split amount and the split fee of {SPLIT_FEE} microcredits"
+        );
+        let inputs = vec![
+            Value::Record(record),
+            Value::from_str(&format!("{amount}u64"))?,
+        ];
+        let transaction = self.create_credits_transaction("split", inputs, 0, None, password)?;
+
+        println!("Attempting to broadcast split transaction");
+        self.broadcast_transaction(transaction.clone())?;
+        Ok(transaction.id())
+    }
+
+    // Create a transaction executing a function of the credits program
+    fn create_credits_transaction(
+        &self,
+        function: &str,
+        inputs: Vec<Value<N>>,
+        priority_fee: u64,
+        fee_record: Option<Record<N, Plaintext<N>>>,
+        password: Option<&str>,
+    ) -> Result<Transaction<N>> {
+        let query = Query::from(self.api_client()?.base_url());
+        let private_key = self.get_private_key(password)?;
+        let rng = &mut rand::thread_rng();
+
+        // Use the VM of the program manager, the credits program is always loaded
+        self.vm()?.execute(
+            &private_key,
+            ("credits.aleo", function),
+            inputs.iter(),
+            fee_record,
+            priority_fee,
+            Some(query),
+            rng,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use PlannedRecord::{Existing, Output};
+
+    #[test]
+    fn test_no_operations_are_planned_for_sufficient_records() {
+        let plan =
+            RecordPlan::new(&[50u64, 10, 200], 40, 10, SelectionStrategy::default()).unwrap();
+        assert!(plan.is_empty());
+        assert_eq!(plan.amount_record, Existing(50));
+        assert_eq!(plan.fee_record, Some(Existing(10)));
+    }
+
+    #[test]
+    fn test_joins_are_planned_for_fragmented_records() {
+        let plan =
+            RecordPlan::new(&[50u64, 10, 40, 30], 100, 10, SelectionStrategy::default()).unwrap();
+        assert_eq!(
+            plan.operations,
+            vec![
+                RecordOperation::Join(Existing(50), Existing(40)),
+                RecordOperation::Join(
+                    Output {
+                        operation: 0,
+                        output: 0
+                    },
+                    Existing(30)
+                ),
+            ]
+        );
+        assert_eq!(
+            plan.amount_record,
+            Output {
+                operation: 1,
+                output: 0
+            }
+        );
+        assert_eq!(plan.fee_record, Some(Existing(10)));
+        assert_eq!(plan.operations[0].event_type(), EventTypeCommon::Join);
+    }
+
+    #[test]
+    fn test_splits_are_planned_for_single_records() {
+        let plan =
+            RecordPlan::new(&[100_000u64], 40_000, 10_000, SelectionStrategy::default()).unwrap();
+        assert_eq!(
+            plan.operations,
+            vec![RecordOperation::Split(Existing(100_000), 40_000)]
+        );
+        assert_eq!(
+            plan.fee_record,
+            Some(Output {
+                operation: 0,
+                output: 1
+            })
+        );
+
+        // Ensure the split fee is accounted for
+        assert!(
+            RecordPlan::new(&[55_000u64], 40_000, 10_000, SelectionStrategy::default()).is_err()
+        );
+    }
+}
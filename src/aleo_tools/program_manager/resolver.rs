@@ -23,7 +23,9 @@ use snarkvm::package::Package;
 use crate::errors::{AvailError, AvailErrorType};
 
 impl<N: Network> ProgramManager<N> {
-    /// Find a program by first looking on disk, and if not found, on the aleo network
+    /// Find a program by first looking on disk, and if not found, on the aleo network. Programs
+    /// found on the network are served from the program cache of the network client once fetched,
+    /// so an offline client still finds them.
     pub fn find_program(&self, program_id: &ProgramID<N>) -> Result<Program<N>> {
         self.find_program_on_disk(program_id)
             .or_else(|_| self.find_program_on_chain(program_id))
@@ -118,7 +120,8 @@ mod tests {
     use crate::aleo_tools::{
         api::AleoAPIClient,
         test_utils::{
-            random_program_id, setup_directory, teardown_directory, HELLO_PROGRAM, IMPORT_PROGRAM,
+            random_program_id, setup_directory, teardown_directory, MockLedger, HELLO_PROGRAM,
+            IMPORT_PROGRAM,
         },
     };
     use crate::models::constants::TESTNET_PRIVATE_KEY;
@@ -328,4 +331,24 @@ mod tests {
         let imports = program_manager.find_program_imports(&bad_import_program);
         assert!(imports.is_err());
     }
+
+    #[test]
+    fn test_network_program_imports_are_resolved_offline() {
+        let credits = Program::<Testnet3>::credits().unwrap();
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_program(credits.clone());
+        let private_key = PrivateKey::<Testnet3>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+        let program_manager =
+            ProgramManager::<Testnet3>::new(Some(private_key), None, Some(ledger.client()), None)
+                .unwrap();
+
+        // Ensure imports are found on the ledger
+        let test_program = Program::<Testnet3>::from_str(IMPORT_PROGRAM).unwrap();
+        let imports = program_manager.find_program_imports(&test_program).unwrap();
+        assert_eq!(imports, vec![credits]);
+
+        // Ensure programs missing from the ledger are not found
+        let program_id = ProgramID::<Testnet3>::from_str(&random_program_id(16)).unwrap();
+        assert!(program_manager.find_program_on_chain(&program_id).is_err());
+    }
 }
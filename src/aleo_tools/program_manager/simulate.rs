@@ -0,0 +1,596 @@
+use super::*;
+use snarkvm::circuit::Aleo;
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+// Mapping values are keyed by the string representation of their key
+type MappingKey<N> = (ProgramID<N>, Identifier<N>, String);
+
+/// A mapping read, write or removal performed by a finalize block
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MappingAccess<N: Network> {
+    /// A value was read, or found to be missing
+    Read {
+        program_id: ProgramID<N>,
+        mapping_name: Identifier<N>,
+        key: Plaintext<N>,
+        value: Option<Value<N>>,
+    },
+    /// A value was inserted or updated
+    Write {
+        program_id: ProgramID<N>,
+        mapping_name: Identifier<N>,
+        key: Plaintext<N>,
+        value: Value<N>,
+    },
+    /// A value was removed
+    Remove {
+        program_id: ProgramID<N>,
+        mapping_name: Identifier<N>,
+        key: Plaintext<N>,
+    },
+}
+
+/// Values of program mappings to simulate executions against, instead of the values on chain.
+/// Keys missing from the snapshot are considered absent from their mapping.
+#[derive(Clone, Debug)]
+pub struct MappingSnapshot<N: Network> {
+    values: HashMap<MappingKey<N>, Value<N>>,
+    block_height: Option<u32>,
+}
+
+impl<N: Network> MappingSnapshot<N> {
+    pub fn new() -> Self {
+        Self {
+            values: HashMap::new(),
+            block_height: None,
+        }
+    }
+
+    /// Set the height of the latest block the snapshot was taken at. Simulations run the
+    /// finalize blocks as the next block would.
+    pub fn with_block_height(mut self, block_height: u32) -> Self {
+        self.block_height = Some(block_height);
+        self
+    }
+
+    /// Get the height of the latest block the snapshot was taken at, if it was set
+    pub fn block_height(&self) -> Option<u32> {
+        self.block_height
+    }
+
+    /// Set the value stored under a key of a program mapping
+    pub fn insert(
+        &mut self,
+        program_id: impl TryInto<ProgramID<N>>,
+        mapping_name: impl TryInto<Identifier<N>>,
+        key: &Plaintext<N>,
+        value: Value<N>,
+    ) -> Result<()> {
+        let program_id = program_id
+            .try_into()
+            .map_err(|_| anyhow!("Invalid program ID"))?;
+        let mapping_name = mapping_name
+            .try_into()
+            .map_err(|_| anyhow!("Invalid mapping name"))?;
+        self.values
+            .insert((program_id, mapping_name, key.to_string()), value);
+        Ok(())
+    }
+
+    /// Get the value stored under a key of a program mapping, if any
+    pub fn get(
+        &self,
+        program_id: &ProgramID<N>,
+        mapping_name: &Identifier<N>,
+        key: &Plaintext<N>,
+    ) -> Option<&Value<N>> {
+        self.values
+            .get(&(*program_id, *mapping_name, key.to_string()))
+    }
+}
+
+impl<N: Network> Default for MappingSnapshot<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The result of simulating the execution of a program function
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Simulation<N: Network> {
+    /// The outputs of the function, decrypted
+    pub outputs: Vec<Value<N>>,
+    /// The records output by the function, decrypted
+    pub records: Vec<Record<N, Plaintext<N>>>,
+    /// The mapping accesses performed by the finalize blocks of the execution, in order
+    pub mapping_accesses: Vec<MappingAccess<N>>,
+    /// The reason the finalize blocks of the execution failed, if they did. A transaction whose
+    /// finalize fails is rejected, and only its fee is spent.
+    pub finalize_error: Option<String>,
+}
+
+impl<N: Network> Simulation<N> {
+    /// Whether the finalize blocks of the execution would succeed
+    pub fn is_finalize_successful(&self) -> bool {
+        self.finalize_error.is_none()
+    }
+}
+
+impl<N: Network> ProgramManager<N> {
+    /// Simulate the execution of a program function locally, without proving it or spending any
+    /// fee, to see what it will do once broadcast.
+    ///
+    /// The program is taken from the program manager if it was added to it, otherwise from the
+    /// local program directory or the network. The finalize blocks of the execution are run
+    /// against the mapping values of the snapshot if one is given, otherwise against the values
+    /// on chain, and the mappings are left untouched. Failures of the function itself are
+    /// returned as errors, while failures of its finalize blocks, e.g. an underflow, are reported
+    /// in the simulation. The finalize blocks run at the height following the block height of the
+    /// snapshot if it has one, otherwise the latest block height on chain if a network client is
+    /// configured, otherwise height 1.
+    ///
+    /// Finalize commands halt by panicking, e.g. on overflows. The panic is caught and reported in
+    /// the simulation, but the panic hook still runs, so the default hook prints the panic message
+    /// to stderr. Install a panic hook with [`std::panic::set_hook`] to silence it.
+    pub fn simulate<A: Aleo<Network = N>>(
+        &self,
+        program_id: impl TryInto<ProgramID<N>>,
+        function: impl TryInto<Identifier<N>>,
+        inputs: impl ExactSizeIterator<Item = impl TryInto<Value<N>>>,
+        snapshot: Option<&MappingSnapshot<N>>,
+        password: Option<&str>,
+    ) -> Result<Simulation<N>> {
+        let program_id = program_id
+            .try_into()
+            .map_err(|_| anyhow!("Invalid program ID"))?;
+        let program = match self.contains_program(program_id)? {
+            true => self.get_program(program_id)?,
+            false => self.find_program(&program_id)?,
+        };
+        let function_name = Self::check_function(&program, function)?;
+        let vm = self.load_program_into_vm(&program)?;
+
+        // Run the function without proving it
+        println!("Simulating {program_id:?}/{function_name:?}..");
+        let private_key = self.get_private_key(password)?;
+        let rng = &mut rand::thread_rng();
+        let authorization = vm.authorize(&private_key, program_id, function_name, inputs, rng)?;
+        let process = vm.process();
+        let process = process.read();
+        let response = process.evaluate::<A>(authorization)?;
+
+        // Run the finalize blocks of the futures output by the function, as the next block would
+        let fetch =
+            |program_id: &ProgramID<N>, mapping_name: &Identifier<N>, key: &Plaintext<N>| {
+                match snapshot {
+                    Some(snapshot) => Ok(snapshot.get(program_id, mapping_name, key).cloned()),
+                    None => self.fetch_mapping_value(program_id, mapping_name, key),
+                }
+            };
+        let store = SimulatedStore {
+            values: Default::default(),
+            accesses: Default::default(),
+            fetch: &fetch,
+        };
+        let latest_height = match (
+            snapshot.and_then(MappingSnapshot::block_height),
+            &self.api_client,
+        ) {
+            (Some(block_height), _) => block_height,
+            (None, Some(api_client)) => api_client.latest_height()?,
+            (None, None) => 0,
+        };
+        let state = FinalizeGlobalState::new::<N>(
+            0,
+            latest_height.saturating_add(1),
+            0u128,
+            0u128,
+            N::BlockHash::default(),
+        )?;
+        let mut finalize_error = None;
+        for output in response.outputs() {
+            if let Value::Future(future) = output {
+                if let Err(error) = Self::simulate_finalize(&process, &store, state, future) {
+                    finalize_error = Some(error.to_string());
+                    break;
+                }
+            }
+        }
+
+        match &finalize_error {
+            Some(error) => {
+                println!("❌ The finalize of {program_id:?}/{function_name:?} would fail: {error}")
+            }
+            None => println!("✅ Simulation of {program_id:?}/{function_name:?} succeeded"),
+        }
+        let records = response
+            .outputs()
+            .iter()
+            .filter_map(|output| match output {
+                Value::Record(record) => Some(record.clone()),
+                _ => None,
+            })
+            .collect();
+        Ok(Simulation {
+            outputs: response.outputs().to_vec(),
+            records,
+            mapping_accesses: store.accesses.into_inner(),
+            finalize_error,
+        })
+    }
+
+    // Get a value of a mapping on chain, or `None` if its key is not in the mapping
+    fn fetch_mapping_value(
+        &self,
+        program_id: &ProgramID<N>,
+        mapping_name: &Identifier<N>,
+        key: &Plaintext<N>,
+    ) -> Result<Option<Value<N>>> {
+        self.api_client()?
+            .find_mapping_value(*program_id, *mapping_name, key)
+    }
+
+    // Run the finalize block of a future, and the finalize blocks of the futures it awaits,
+    // against the store
+    fn simulate_finalize(
+        process: &Process<N>,
+        store: &SimulatedStore<N>,
+        state: FinalizeGlobalState,
+        future: &Future<N>,
+    ) -> Result<()> {
+        let stack: &Stack<N> = process.get_stack(future.program_id())?;
+        let function = stack.program().get_function(future.function_name())?;
+        let Some(finalize) = function.finalize_logic() else {
+            return Ok(());
+        };
+
+        let mut registers = FinalizeRegisters::new(
+            state,
+            N::TransitionID::default(),
+            *future.function_name(),
+            stack.get_finalize_types(future.function_name())?.clone(),
+        );
+        ensure!(
+            finalize.inputs().len() == future.arguments().len(),
+            "The future of {}/{} has the wrong number of arguments",
+            future.program_id(),
+            future.function_name()
+        );
+        for (input, argument) in finalize.inputs().iter().zip(future.arguments()) {
+            let value = match argument {
+                Argument::Plaintext(plaintext) => Value::Plaintext(plaintext.clone()),
+                Argument::Future(future) => Value::Future(future.clone()),
+            };
+            registers.store(stack, input.register(), value)?;
+        }
+
+        let commands = finalize.commands();
+        let mut counter = 0;
+        while counter < commands.len() {
+            counter = match &commands[counter] {
+                Command::Await(await_) => {
+                    let operand = Operand::Register(await_.register().clone());
+                    let Value::Future(awaited) = registers.load(stack, &operand)? else {
+                        bail!("Awaited register {} is not a future", await_.register());
+                    };
+                    Self::simulate_finalize(process, store, state, &awaited)?;
+                    counter + 1
+                }
+                Command::BranchEq(branch) => {
+                    let is_equal = registers.load(stack, branch.first())?
+                        == registers.load(stack, branch.second())?;
+                    match is_equal {
+                        true => Self::branch_position(finalize, branch.position())?,
+                        false => counter + 1,
+                    }
+                }
+                Command::BranchNeq(branch) => {
+                    let is_equal = registers.load(stack, branch.first())?
+                        == registers.load(stack, branch.second())?;
+                    match is_equal {
+                        true => counter + 1,
+                        false => Self::branch_position(finalize, branch.position())?,
+                    }
+                }
+                command => {
+                    // Commands halt by panicking, e.g. on arithmetic overflows and underflows.
+                    // The panic hook still runs, see `simulate`
+                    catch_unwind(AssertUnwindSafe(|| {
+                        command.finalize(stack, store, &mut registers)
+                    }))
+                    .map_err(|_| anyhow!("'{command}' halted"))?
+                    .map_err(|error| anyhow!("'{command}' failed: {error}"))?;
+                    counter + 1
+                }
+            };
+        }
+        Ok(())
+    }
+
+    fn branch_position(finalize: &Finalize<N>, position: &Identifier<N>) -> Result<usize> {
+        finalize
+            .positions()
+            .get(position)
+            .copied()
+            .ok_or_else(|| anyhow!("Position {position} does not exist in the finalize block"))
+    }
+}
+
+// Finalize store serving the values of the mappings from a fetch function, and applying the
+// writes of a simulation in memory only, while recording every access
+struct SimulatedStore<'a, N: Network> {
+    values: RefCell<HashMap<MappingKey<N>, Option<Value<N>>>>,
+    accesses: RefCell<Vec<MappingAccess<N>>>,
+    #[allow(clippy::type_complexity)]
+    fetch: &'a dyn Fn(&ProgramID<N>, &Identifier<N>, &Plaintext<N>) -> Result<Option<Value<N>>>,
+}
+
+impl<N: Network> SimulatedStore<'_, N> {
+    fn read(
+        &self,
+        program_id: ProgramID<N>,
+        mapping_name: Identifier<N>,
+        key: &Plaintext<N>,
+    ) -> Result<Option<Value<N>>> {
+        let mapping_key = (program_id, mapping_name, key.to_string());
+        let cached = self.values.borrow().get(&mapping_key).cloned();
+        let value = match cached {
+            Some(value) => value,
+            None => {
+                let value = (self.fetch)(&program_id, &mapping_name, key)?;
+                self.values.borrow_mut().insert(mapping_key, value.clone());
+                value
+            }
+        };
+        self.accesses.borrow_mut().push(MappingAccess::Read {
+            program_id,
+            mapping_name,
+            key: key.clone(),
+            value: value.clone(),
+        });
+        Ok(value)
+    }
+
+    fn write(
+        &self,
+        program_id: ProgramID<N>,
+        mapping_name: Identifier<N>,
+        key: Plaintext<N>,
+        value: Value<N>,
+    ) -> FinalizeOperation<N> {
+        self.values.borrow_mut().insert(
+            (program_id, mapping_name, key.to_string()),
+            Some(value.clone()),
+        );
+        self.accesses.borrow_mut().push(MappingAccess::Write {
+            program_id,
+            mapping_name,
+            key,
+            value,
+        });
+        // The operations only serve to update the finalize tree of a ledger, which a simulation
+        // does not have
+        FinalizeOperation::UpdateKeyValue(Field::zero(), 0, Field::zero(), Field::zero())
+    }
+}
+
+impl<N: Network> FinalizeStoreTrait<N> for SimulatedStore<'_, N> {
+    // Mappings are checked to exist when programs are added to the process
+    fn contains_mapping_confirmed(
+        &self,
+        _program_id: &ProgramID<N>,
+        _mapping_name: &Identifier<N>,
+    ) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn contains_key_speculative(
+        &self,
+        program_id: ProgramID<N>,
+        mapping_name: Identifier<N>,
+        key: &Plaintext<N>,
+    ) -> Result<bool> {
+        Ok(self.read(program_id, mapping_name, key)?.is_some())
+    }
+
+    fn get_value_speculative(
+        &self,
+        program_id: ProgramID<N>,
+        mapping_name: Identifier<N>,
+        key: &Plaintext<N>,
+    ) -> Result<Option<Value<N>>> {
+        self.read(program_id, mapping_name, key)
+    }
+
+    fn insert_key_value(
+        &self,
+        program_id: ProgramID<N>,
+        mapping_name: Identifier<N>,
+        key: Plaintext<N>,
+        value: Value<N>,
+    ) -> Result<FinalizeOperation<N>> {
+        Ok(self.write(program_id, mapping_name, key, value))
+    }
+
+    fn update_key_value(
+        &self,
+        program_id: ProgramID<N>,
+        mapping_name: Identifier<N>,
+        key: Plaintext<N>,
+        value: Value<N>,
+    ) -> Result<FinalizeOperation<N>> {
+        Ok(self.write(program_id, mapping_name, key, value))
+    }
+
+    fn remove_key_value(
+        &self,
+        program_id: ProgramID<N>,
+        mapping_name: Identifier<N>,
+        key: &Plaintext<N>,
+    ) -> Result<Option<FinalizeOperation<N>>> {
+        self.values
+            .borrow_mut()
+            .insert((program_id, mapping_name, key.to_string()), None);
+        self.accesses.borrow_mut().push(MappingAccess::Remove {
+            program_id,
+            mapping_name,
+            key: key.clone(),
+        });
+        Ok(Some(FinalizeOperation::RemoveKeyValue(Field::zero(), 0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aleo_tools::test_utils::MockLedger;
+    use crate::models::constants::TESTNET_PRIVATE_KEY;
+    use snarkvm::circuit::AleoV0;
+
+    const WITHDRAW_PROGRAM: &str = "program simulate_test.aleo;
+
+mapping balances:
+    key as address.public;
+    value as u64.public;
+
+function withdraw:
+    input r0 as u64.public;
+    async withdraw self.caller r0 into r1;
+    output r0 as u64.private;
+    output r1 as simulate_test.aleo/withdraw.future;
+
+finalize withdraw:
+    input r0 as address.public;
+    input r1 as u64.public;
+    get.or_use balances[r0] 0u64 into r2;
+    sub r2 r1 into r3;
+    set r3 into balances[r0];
+
+function unlock:
+    input r0 as u32.public;
+    async unlock r0 into r1;
+    output r1 as simulate_test.aleo/unlock.future;
+
+finalize unlock:
+    input r0 as u32.public;
+    lte r0 block.height into r1;
+    assert.eq r1 true;
+";
+
+    #[test]
+    fn test_simulations_report_mapping_accesses_and_finalize_failures() {
+        let private_key = PrivateKey::<Testnet3>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+        let address = Plaintext::from(Literal::Address(Address::try_from(&private_key).unwrap()));
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_genesis_block().unwrap();
+        ledger
+            .set_mapping_value(
+                "simulate_test.aleo",
+                "balances",
+                &address.to_string(),
+                Value::from_str("10u64").unwrap(),
+            )
+            .unwrap();
+        let mut program_manager =
+            ProgramManager::<Testnet3>::new(Some(private_key), None, Some(ledger.client()), None)
+                .unwrap();
+        program_manager
+            .add_program(&Program::from_str(WITHDRAW_PROGRAM).unwrap())
+            .unwrap();
+
+        // Ensure the values on chain are read and the writes are reported
+        let simulation = program_manager
+            .simulate::<AleoV0>(
+                "simulate_test.aleo",
+                "withdraw",
+                ["4u64"].into_iter(),
+                None,
+                None,
+            )
+            .unwrap();
+        assert!(simulation.is_finalize_successful());
+        assert_eq!(simulation.outputs[0], Value::from_str("4u64").unwrap());
+        assert!(simulation.records.is_empty());
+        let program_id = ProgramID::from_str("simulate_test.aleo").unwrap();
+        let mapping_name = Identifier::from_str("balances").unwrap();
+        assert_eq!(
+            simulation.mapping_accesses,
+            vec![
+                MappingAccess::Read {
+                    program_id,
+                    mapping_name,
+                    key: address.clone(),
+                    value: Some(Value::from_str("10u64").unwrap()),
+                },
+                MappingAccess::Write {
+                    program_id,
+                    mapping_name,
+                    key: address.clone(),
+                    value: Value::from_str("6u64").unwrap(),
+                },
+            ]
+        );
+
+        // Ensure underflows are reported instead of returned as errors
+        let simulation = program_manager
+            .simulate::<AleoV0>(
+                "simulate_test.aleo",
+                "withdraw",
+                ["11u64"].into_iter(),
+                None,
+                None,
+            )
+            .unwrap();
+        assert!(!simulation.is_finalize_successful());
+        assert_eq!(simulation.mapping_accesses.len(), 1);
+
+        // Ensure snapshots are used instead of the values on chain
+        let mut snapshot = MappingSnapshot::new();
+        snapshot
+            .insert(
+                program_id,
+                mapping_name,
+                &address,
+                Value::from_str("20u64").unwrap(),
+            )
+            .unwrap();
+        let simulation = program_manager
+            .simulate::<AleoV0>(
+                "simulate_test.aleo",
+                "withdraw",
+                ["11u64"].into_iter(),
+                Some(&snapshot),
+                None,
+            )
+            .unwrap();
+        assert!(simulation.is_finalize_successful());
+
+        // Ensure the block height is taken from the network, unless the snapshot has one
+        let simulation = program_manager
+            .simulate::<AleoV0>(
+                "simulate_test.aleo",
+                "unlock",
+                ["5u32"].into_iter(),
+                Some(&snapshot),
+                None,
+            )
+            .unwrap();
+        assert!(!simulation.is_finalize_successful());
+        let snapshot = snapshot.with_block_height(10);
+        let simulation = program_manager
+            .simulate::<AleoV0>(
+                "simulate_test.aleo",
+                "unlock",
+                ["5u32"].into_iter(),
+                Some(&snapshot),
+                None,
+            )
+            .unwrap();
+        assert!(simulation.is_finalize_successful());
+    }
+}
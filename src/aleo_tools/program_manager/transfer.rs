@@ -1,7 +1,8 @@
 use super::*;
+use crate::aleo_tools::coin_selection::{select_coins, SelectionStrategy};
 use crate::aleo_tools::program_manager::Credits;
 use serde::{Deserialize, Serialize};
-use snarkvm::ledger::query::*;
+use snarkvm::ledger::{block::Transaction, query::*};
 
 /// Transfer Type to Perform
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
@@ -16,6 +17,29 @@ pub enum TransferType {
     PublicToPrivate,
 }
 
+impl TransferType {
+    /// Whether the transfer spends a record for its amount
+    pub fn requires_record(&self) -> bool {
+        matches!(self, Self::Private | Self::PrivateToPublic)
+    }
+}
+
+/// A transfer of a batch, and its outcome
+#[derive(Clone, Debug)]
+pub struct BatchTransferResult<N: Network> {
+    pub recipient: Address<N>,
+    pub amount: u64,
+    pub transfer_type: TransferType,
+    /// The ID of the broadcast transaction, or the reason the transfer failed
+    pub result: std::result::Result<N::TransactionID, String>,
+}
+
+impl<N: Network> BatchTransferResult<N> {
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
 impl<N: Network> ProgramManager<N> {
     //TODO - Remove unwraps
     /// Executes a transfer to the specified recipient_address with the specified amount and fee.
@@ -32,6 +56,36 @@ impl<N: Network> ProgramManager<N> {
         fee_record: Option<Record<N, Plaintext<N>>>,
         program_id: &str,
     ) -> Result<N::TransactionID> {
+        let execution = self.build_transfer(
+            amount,
+            fee,
+            recipient_address,
+            transfer_type,
+            password,
+            amount_record,
+            fee_record,
+            program_id,
+        )?;
+
+        self.broadcast_transaction(execution.clone())?;
+
+        Ok(execution.id())
+    }
+
+    /// Build and sign a transfer transaction without broadcasting it, so it can be inspected,
+    /// stored or broadcast later with [`ProgramManager::broadcast_transaction`]
+    #[allow(clippy::too_many_arguments)]
+    pub fn build_transfer(
+        &self,
+        amount: u64,
+        fee: u64,
+        recipient_address: Address<N>,
+        transfer_type: TransferType,
+        password: Option<&str>,
+        amount_record: Option<Record<N, Plaintext<N>>>,
+        fee_record: Option<Record<N, Plaintext<N>>>,
+        program_id: &str,
+    ) -> Result<Transaction<N>> {
         // Ensure records provided have enough credits to cover the transfer amount and fee
         if let Some(amount_record) = amount_record.as_ref() {
             ensure!(
@@ -47,7 +101,7 @@ impl<N: Network> ProgramManager<N> {
         }
 
         // Specify the network state query
-        let query = Query::from(self.api_client.as_ref().unwrap().base_url());
+        let query = Query::from(self.api_client()?.base_url());
 
         // Retrieve the private key.
         let private_key = self.get_private_key(password)?;
@@ -56,51 +110,12 @@ impl<N: Network> ProgramManager<N> {
         let execution = {
             let rng = &mut rand::thread_rng();
 
-            // Initialize a VM
-            let store = ConsensusStore::<N, ConsensusMemory<N>>::open(None)?;
-            let vm = VM::from(store)?;
+            // Use the VM of the program manager, the credits program is always loaded
+            let vm = self.vm()?;
 
             // Prepare the inputs for a transfer.
-            let (transfer_function, inputs) = match transfer_type {
-                TransferType::Public => {
-                    let inputs = vec![
-                        Value::from_str(&recipient_address.to_string())?,
-                        Value::from_str(&format!("{}u64", amount))?,
-                    ];
-                    ("transfer_public", inputs)
-                }
-                TransferType::Private => {
-                    if amount_record.is_none() {
-                        bail!("Amount record must be specified for private transfers");
-                    } else {
-                        let inputs = vec![
-                            Value::Record(amount_record.unwrap()),
-                            Value::from_str(&recipient_address.to_string())?,
-                            Value::from_str(&format!("{}u64", amount))?,
-                        ];
-                        ("transfer_private", inputs)
-                    }
-                }
-                TransferType::PublicToPrivate => {
-                    let inputs = vec![
-                        Value::from_str(&recipient_address.to_string())?,
-                        Value::from_str(&format!("{}u64", amount))?,
-                    ];
-                    ("transfer_public_to_private", inputs)
-                }
-                TransferType::PrivateToPublic => {
-                    if amount_record.is_none() {
-                        bail!("Amount record must be specified for private transfers");
-                    } else {
-                        let inputs = vec![
-                            Value::Record(amount_record.unwrap()),
-                            Value::from_str(&recipient_address.to_string())?,
-                            Value::from_str(&format!("{}u64", amount))?,
-                        ];
-                        ("transfer_private_to_public", inputs)
-                    }
-                }
-            };
+            let (transfer_function, inputs) =
+                Self::transfer_inputs(transfer_type, recipient_address, amount, amount_record)?;
 
             // Create a new transaction.
             vm.execute(
@@ -114,11 +129,247 @@ impl<N: Network> ProgramManager<N> {
             )?
         };
 
-        self.broadcast_transaction(execution.clone())?;
+        Ok(execution)
+    }
+}
 
-        Ok(execution.id())
+impl<N: Network> ProgramManager<N> {
+    /// Executes a batch of transfers, each to a recipient with an amount and a transfer type, and
+    /// a fee of `fee` microcredits per transfer.
+    ///
+    /// Records for the amounts, and for the fees if `private_fee` is set, are selected from the
+    /// given records with the coin-selection strategy, so every record is spent at most once. All
+    /// transactions are built by the VM of the program manager. A failing transfer does not stop
+    /// the batch, the result of every transfer is returned in the order of the batch. Records
+    /// selected for a transfer which could not be built are returned to the pool for the following
+    /// transfers.
+    pub fn transfer_batch(
+        &self,
+        transfers: &[(Address<N>, u64, TransferType)],
+        fee: u64,
+        private_fee: bool,
+        records: Vec<Record<N, Plaintext<N>>>,
+        strategy: SelectionStrategy,
+        password: Option<&str>,
+    ) -> Result<Vec<BatchTransferResult<N>>> {
+        let query = Query::from(self.api_client()?.base_url());
+        let private_key = self.get_private_key(password)?;
+        let rng = &mut rand::thread_rng();
+
+        let mut pool = records;
+        let mut results = Vec::with_capacity(transfers.len());
+        for (recipient, amount, transfer_type) in transfers.iter().copied() {
+            let result = (|| {
+                // Select the records paying for the transfer
+                let amount_needed = if transfer_type.requires_record() {
+                    amount
+                } else {
+                    0
+                };
+                let fee_needed = if private_fee { fee } else { 0 };
+                let total_needed = amount_needed.checked_add(fee_needed).ok_or_else(|| {
+                    format!("The amount of {amount} and the fee of {fee} microcredits overflow")
+                })?;
+                let (amount_record, fee_record) = match total_needed {
+                    0 => (None, None),
+                    _ => {
+                        let selection = select_coins(&pool, amount_needed, fee_needed, strategy)
+                            .map_err(|error| error.to_string())?;
+                        if selection.is_join_required() {
+                            return Err(format!(
+                                "{} records must be joined before the transfer can be made",
+                                selection.joins() + 1
+                            ));
+                        }
+                        pool.retain(|record| {
+                            Some(record) != selection.amount_record()
+                                && Some(record) != selection.fee_record()
+                        });
+                        (
+                            selection.amount_record().cloned(),
+                            selection.fee_record().cloned(),
+                        )
+                    }
+                };
+
+                // Build the transaction with the VM of the program manager
+                let transaction = (|| {
+                    let (transfer_function, inputs) = Self::transfer_inputs(
+                        transfer_type,
+                        recipient,
+                        amount,
+                        amount_record.clone(),
+                    )?;
+                    self.vm()?.execute(
+                        &private_key,
+                        ("credits.aleo", transfer_function),
+                        inputs.iter(),
+                        fee_record.clone(),
+                        fee,
+                        Some(query.clone()),
+                        rng,
+                    )
+                })();
+                let transaction = match transaction {
+                    Ok(transaction) => transaction,
+                    Err(error) => {
+                        pool.extend(amount_record.into_iter().chain(fee_record));
+                        return Err(error.to_string());
+                    }
+                };
+
+                self.broadcast_transaction(transaction.clone())
+                    .map_err(|error| error.to_string())?;
+                Ok(transaction.id())
+            })();
+
+            if let Err(error) = &result {
+                println!("❌ Transfer of {amount} microcredits to {recipient} failed: {error}");
+            }
+            results.push(BatchTransferResult {
+                recipient,
+                amount,
+                transfer_type,
+                result,
+            });
+        }
+        Ok(results)
+    }
+
+    // Get the credits program function and its inputs for a transfer
+    pub(super) fn transfer_inputs(
+        transfer_type: TransferType,
+        recipient_address: Address<N>,
+        amount: u64,
+        amount_record: Option<Record<N, Plaintext<N>>>,
+    ) -> Result<(&'static str, Vec<Value<N>>)> {
+        let recipient = Value::from_str(&recipient_address.to_string())?;
+        let amount = Value::from_str(&format!("{}u64", amount))?;
+        let transfer = match transfer_type {
+            TransferType::Public => ("transfer_public", vec![recipient, amount]),
+            TransferType::PublicToPrivate => {
+                ("transfer_public_to_private", vec![recipient, amount])
+            }
+            TransferType::Private | TransferType::PrivateToPublic => {
+                let Some(amount_record) = amount_record else {
+                    bail!("Amount record must be specified for private transfers");
+                };
+                let function = match transfer_type {
+                    TransferType::Private => "transfer_private",
+                    _ => "transfer_private_to_public",
+                };
+                (
+                    function,
+                    vec![Value::Record(amount_record), recipient, amount],
+                )
+            }
+        };
+        Ok(transfer)
     }
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use crate::aleo_tools::test_utils::{MockLedger, MockNodeServer, RECORD_5_MICROCREDITS};
+    use crate::models::constants::{TESTNET3_ADDRESS, TESTNET_PRIVATE_KEY};
+
+    #[test]
+    fn test_batch_transfers_report_failures_per_recipient() {
+        let private_key = PrivateKey::<Testnet3>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        let program_manager =
+            ProgramManager::<Testnet3>::new(Some(private_key), None, Some(ledger.client()), None)
+                .unwrap();
+        let recipient = Address::<Testnet3>::from_str(TESTNET3_ADDRESS).unwrap();
+        let record =
+            Record::<Testnet3, Plaintext<Testnet3>>::from_str(RECORD_5_MICROCREDITS).unwrap();
+
+        let results = program_manager
+            .transfer_batch(
+                &[
+                    (recipient, 10, TransferType::Private),
+                    (recipient, 5, TransferType::PrivateToPublic),
+                    (recipient, 1, TransferType::Private),
+                ],
+                1,
+                true,
+                vec![record],
+                SelectionStrategy::default(),
+                None,
+            )
+            .unwrap();
+
+        // Ensure every transfer has a result, in order, and unfunded transfers fail before any
+        // transaction is built
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[1].amount, 5);
+        assert!(results.iter().all(|result| !result.is_ok()));
+        assert!(results[0]
+            .result
+            .as_ref()
+            .unwrap_err()
+            .contains("Insufficient funds"));
+        assert!(ledger.broadcast_transactions().is_empty());
+
+        // Ensure amounts overflowing with the fee are reported instead of wrapping
+        let results = program_manager
+            .transfer_batch(
+                &[(recipient, u64::MAX, TransferType::Private)],
+                1,
+                true,
+                vec![],
+                SelectionStrategy::default(),
+                None,
+            )
+            .unwrap();
+        assert!(results[0].result.as_ref().unwrap_err().contains("overflow"));
+    }
+
+    #[test]
+    fn test_batch_transfers_share_the_vm() {
+        let private_key = PrivateKey::<Testnet3>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_genesis_block().unwrap();
+        // The network state is queried over HTTP while building the transactions
+        let server = MockNodeServer::start(ledger).unwrap();
+        let program_manager = ProgramManager::<Testnet3>::new(
+            Some(private_key),
+            None,
+            Some(server.client("testnet3")),
+            None,
+        )
+        .unwrap();
+        let vm = program_manager.vm().unwrap() as *const _;
+        let recipient = Address::<Testnet3>::from_str(TESTNET3_ADDRESS).unwrap();
+
+        let results = program_manager
+            .transfer_batch(
+                &[
+                    (recipient, 1, TransferType::Public),
+                    (recipient, 2, TransferType::PublicToPrivate),
+                ],
+                0,
+                false,
+                vec![],
+                SelectionStrategy::default(),
+                None,
+            )
+            .unwrap();
+
+        // Ensure every transfer was built by the VM of the program manager and broadcast
+        assert!(results.iter().all(|result| result.is_ok()));
+        assert!(std::ptr::eq(vm, program_manager.vm().unwrap()));
+        let broadcasts = server.ledger().broadcast_transactions();
+        assert_eq!(
+            broadcasts
+                .iter()
+                .map(|transaction| Ok::<_, String>(transaction.id()))
+                .collect::<Vec<_>>(),
+            results
+                .into_iter()
+                .map(|result| result.result)
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use super::*;
+
+impl<N: Network> ProgramManager<N> {
+    /// Get the VM of the program manager, initializing it on first use.
+    ///
+    /// The VM is kept for the lifetime of the program manager and shared with its clones, so the
+    /// programs loaded into its process and the keys synthesized for them are reused by every
+    /// transaction built, instead of being fetched and synthesized again. It only ever contains
+    /// `credits.aleo` and the programs loaded with [`ProgramManager::load_program_into_vm`].
+    pub fn vm(&self) -> Result<&VM<N, ConsensusMemory<N>>> {
+        self.vm.get_or_try_init(|| {
+            let store = ConsensusStore::<N, ConsensusMemory<N>>::open(None)?;
+            VM::from(store)
+        })
+    }
+
+    /// Load a program and its imports into the VM of the program manager if they are not loaded
+    /// yet, returning the VM. Imports are fetched from the network only if some are missing.
+    pub fn load_program_into_vm(&self, program: &Program<N>) -> Result<&VM<N, ConsensusMemory<N>>> {
+        let vm = self.load_imports_into_vm(program)?;
+        Self::add_to_process(vm, program)?;
+        Ok(vm)
+    }
+
+    /// Load the imports of a program into the VM of the program manager if they are not loaded
+    /// yet, without loading the program itself, e.g. so it can be deployed
+    pub fn load_imports_into_vm(&self, program: &Program<N>) -> Result<&VM<N, ConsensusMemory<N>>> {
+        let vm = self.vm()?;
+        let is_missing_imports = program
+            .imports()
+            .keys()
+            .any(|import_id| !vm.process().read().contains_program(import_id));
+        if is_missing_imports {
+            let imports = self
+                .api_client()?
+                .get_program_imports_from_source(program)?;
+            for import in imports.values() {
+                Self::add_to_process(vm, import)?;
+            }
+        }
+        Ok(vm)
+    }
+
+    /// Get a throwaway VM with a program and its imports loaded if a different version of the
+    /// program is loaded into the VM of the program manager, or `None` if the program can be
+    /// loaded into the shared VM. Used to estimate fees of updated programs without making the
+    /// version on chain unusable by the shared VM.
+    pub fn throwaway_vm_for(
+        &self,
+        program: &Program<N>,
+    ) -> Result<Option<VM<N, ConsensusMemory<N>>>> {
+        let is_different_version_loaded = {
+            let process = self.vm()?.process();
+            let process = process.read();
+            process.contains_program(program.id()) && process.get_program(*program.id())? != program
+        };
+        if !is_different_version_loaded {
+            return Ok(None);
+        }
+
+        let store = ConsensusStore::<N, ConsensusMemory<N>>::open(None)?;
+        let vm = VM::from(store)?;
+        if !program.imports().is_empty() {
+            let imports = self
+                .api_client()?
+                .get_program_imports_from_source(program)?;
+            for import in imports.values() {
+                Self::add_to_process(&vm, import)?;
+            }
+        }
+        Self::add_to_process(&vm, program)?;
+        Ok(Some(vm))
+    }
+
+    /// Discard the VM of the program manager along with the programs loaded into it, e.g. to
+    /// load a different version of a program. Clones of the program manager keep the VM they
+    /// share.
+    pub fn reset_vm(&mut self) {
+        self.vm = Default::default();
+    }
+
+    // Add a program to the process of the VM unless it is already there. Programs cannot be
+    // replaced in a process, so loading a different program with the ID of a loaded program fails.
+    fn add_to_process(vm: &VM<N, ConsensusMemory<N>>, program: &Program<N>) -> Result<()> {
+        let process = vm.process();
+        let mut process = process.write();
+        if process.contains_program(program.id()) {
+            ensure!(
+                process.get_program(*program.id())? == program,
+                "A different version of program {} is already loaded, reset the VM to load it",
+                program.id()
+            );
+            return Ok(());
+        }
+        process.add_program(program)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aleo_tools::test_utils::{HELLO_PROGRAM, HELLO_PROGRAM_2};
+    use crate::models::constants::TESTNET_PRIVATE_KEY;
+
+    #[test]
+    fn test_vm_is_shared_and_loads_programs_once() {
+        let private_key = PrivateKey::<Testnet3>::from_str(TESTNET_PRIVATE_KEY).unwrap();
+        let mut program_manager =
+            ProgramManager::<Testnet3>::new(Some(private_key), None, None, None).unwrap();
+        let program = Program::<Testnet3>::from_str(HELLO_PROGRAM).unwrap();
+
+        // Programs without imports are loaded without a network client
+        program_manager.load_program_into_vm(&program).unwrap();
+        program_manager.load_program_into_vm(&program).unwrap();
+
+        // Ensure clones share the VM
+        let clone = program_manager.clone();
+        assert!(std::ptr::eq(
+            clone.vm().unwrap(),
+            program_manager.vm().unwrap()
+        ));
+        assert!(clone
+            .vm()
+            .unwrap()
+            .process()
+            .read()
+            .contains_program(program.id()));
+
+        // Ensure a different version of a loaded program is rejected until the VM is reset
+        let updated_program = Program::<Testnet3>::from_str(HELLO_PROGRAM_2).unwrap();
+        assert!(program_manager
+            .load_program_into_vm(&updated_program)
+            .is_err());
+
+        // Ensure a throwaway VM is used for a different version, leaving the shared VM as it is
+        assert!(program_manager
+            .throwaway_vm_for(&program)
+            .unwrap()
+            .is_none());
+        let throwaway_vm = program_manager
+            .throwaway_vm_for(&updated_program)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            throwaway_vm
+                .process()
+                .read()
+                .get_program(*program.id())
+                .unwrap(),
+            &updated_program
+        );
+        assert_eq!(
+            program_manager
+                .vm()
+                .unwrap()
+                .process()
+                .read()
+                .get_program(*program.id())
+                .unwrap(),
+            &program
+        );
+
+        program_manager.reset_vm();
+        program_manager
+            .load_program_into_vm(&updated_program)
+            .unwrap();
+        assert_eq!(
+            clone
+                .vm()
+                .unwrap()
+                .process()
+                .read()
+                .get_program(*program.id())
+                .unwrap(),
+            &program
+        );
+    }
+}
@@ -0,0 +1,289 @@
+pub mod checkpoint;
+pub use checkpoint::*;
+
+use std::{ops::ControlFlow, sync::mpsc::Sender, sync::Arc};
+
+use rayon::prelude::*;
+use snarkvm::{ledger::block::Block, prelude::*};
+
+use crate::aleo_tools::api::AleoAPIClient;
+
+/// Number of blocks fetched per request by default
+pub const DEFAULT_BATCH_SIZE: u32 = 50;
+
+/// Progress of a scan over a range of blocks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScanProgress {
+    /// Height the scan started from
+    pub start_height: u32,
+    /// Height of the first block which has not been scanned yet
+    pub scanned_height: u32,
+    /// Height the scan stops at (exclusive)
+    pub end_height: u32,
+    /// Number of records found so far during the scan
+    pub records_found: usize,
+}
+
+impl ScanProgress {
+    /// Share of the blocks scanned so far, between 0 and 1
+    pub fn fraction(&self) -> f64 {
+        let total = self.end_height.saturating_sub(self.start_height);
+        if total == 0 {
+            return 1.0;
+        }
+        let scanned = self.scanned_height.saturating_sub(self.start_height);
+        scanned as f64 / total as f64
+    }
+
+    /// Whether all blocks of the range have been scanned
+    pub fn is_complete(&self) -> bool {
+        self.scanned_height >= self.end_height
+    }
+}
+
+/// Find the records owned by a view key in the given blocks, checking ownership in parallel.
+/// Records are returned with the height of their block, in ledger order.
+pub fn find_owned_records<N: Network>(
+    blocks: Vec<Block<N>>,
+    view_key: &ViewKey<N>,
+) -> Vec<(u32, Field<N>, Record<N, Ciphertext<N>>)> {
+    find_records_by_account(blocks, std::slice::from_ref(view_key))
+        .pop()
+        .unwrap_or_default()
+}
+
+/// Find the records owned by any of the view keys in the given blocks, checking ownership in
+/// parallel. The records of every view key are returned at its index, with the height of their
+/// block, in ledger order.
+pub fn find_records_by_account<N: Network>(
+    blocks: Vec<Block<N>>,
+    view_keys: &[ViewKey<N>],
+) -> Vec<Vec<(u32, Field<N>, Record<N, Ciphertext<N>>)>> {
+    let accounts = view_keys
+        .iter()
+        .map(|view_key| (view_key, view_key.to_address().to_x_coordinate()))
+        .collect::<Vec<_>>();
+    let owned = blocks
+        .into_par_iter()
+        .flat_map_iter(|block| {
+            let height = block.height();
+            block
+                .into_records()
+                .map(move |(commitment, record)| (height, commitment, record))
+        })
+        .filter_map(|(height, commitment, record)| {
+            let owner = accounts
+                .iter()
+                .position(|(view_key, address_x_coordinate)| {
+                    record.is_owner_with_address_x_coordinate(view_key, address_x_coordinate)
+                })?;
+            Some((owner, height, commitment, record))
+        })
+        .collect::<Vec<_>>();
+
+    let mut records = vec![vec![]; view_keys.len()];
+    for (owner, height, commitment, record) in owned {
+        records[owner].push((height, commitment, record));
+    }
+    records
+}
+
+/// An event emitted while scanning the ledger
+#[derive(Clone, Debug)]
+pub enum ScanEvent<N: Network> {
+    /// A record owned by the scanned account was found
+    Record {
+        height: u32,
+        commitment: Field<N>,
+        record: Record<N, Ciphertext<N>>,
+    },
+    /// A batch of blocks was scanned and the checkpoint advanced
+    Progress(ScanProgress),
+}
+
+/// Scanner searching the ledger for the records of an account, in batches of blocks.
+///
+/// After every batch the height reached is saved to a [`CheckpointStore`], so a scan which is
+/// interrupted (e.g. by a failed request or the application closing) resumes where it stopped
+/// instead of starting over. Records are emitted as they are found. Since the checkpoint is only
+/// advanced once a batch has been fully emitted, records of the last batch of an interrupted scan
+/// may be emitted again when it resumes.
+#[derive(Clone, Debug)]
+pub struct RecordScanner<N: Network> {
+    client: AleoAPIClient<N>,
+    store: Arc<dyn CheckpointStore>,
+    batch_size: u32,
+    start_height: u32,
+}
+
+impl<N: Network> RecordScanner<N> {
+    pub fn new(client: AleoAPIClient<N>, store: impl CheckpointStore + 'static) -> Self {
+        Self {
+            client,
+            store: Arc::new(store),
+            batch_size: DEFAULT_BATCH_SIZE,
+            start_height: 0,
+        }
+    }
+
+    /// Set the number of blocks fetched per request, at most 50
+    pub fn with_batch_size(mut self, batch_size: u32) -> Self {
+        self.batch_size = batch_size.clamp(1, DEFAULT_BATCH_SIZE);
+        self
+    }
+
+    /// Set the height to start scanning from for accounts without a checkpoint
+    pub fn with_start_height(mut self, start_height: u32) -> Self {
+        self.start_height = start_height;
+        self
+    }
+
+    /// Get the height of the first block which has not been scanned yet for an account
+    pub fn checkpoint(&self, view_key: &ViewKey<N>) -> Result<Option<u32>> {
+        self.store.load(&Self::checkpoint_key(view_key))
+    }
+
+    /// Forget the checkpoint of an account, so the next scan starts over
+    pub fn reset(&self, view_key: &ViewKey<N>) -> Result<()> {
+        self.store.clear(&Self::checkpoint_key(view_key))
+    }
+
+    /// Scan the ledger for records owned by the view key, starting from its checkpoint, until the
+    /// given end height (exclusive) or the latest block. Every record found and every batch
+    /// scanned is reported to the callback, which may stop the scan by returning
+    /// [`ControlFlow::Break`]. Returns the progress reached.
+    pub fn scan_with(
+        &self,
+        view_key: &ViewKey<N>,
+        end_height: Option<u32>,
+        mut callback: impl FnMut(ScanEvent<N>) -> ControlFlow<()>,
+    ) -> Result<ScanProgress> {
+        let key = Self::checkpoint_key(view_key);
+
+        let start_height = self.store.load(&key)?.unwrap_or(self.start_height);
+        let end_height = match end_height {
+            Some(end_height) => end_height,
+            None => self.client.latest_height()? + 1,
+        };
+        let mut progress = ScanProgress {
+            start_height,
+            scanned_height: start_height,
+            end_height,
+            records_found: 0,
+        };
+
+        // Batches are fetched concurrently, up to the scan concurrency of the client, but emitted
+        // and checkpointed in order
+        let batches = (start_height..end_height)
+            .step_by(self.batch_size as usize)
+            .map(|start| start..start.saturating_add(self.batch_size).min(end_height))
+            .collect::<Vec<_>>();
+        for group in batches.chunks(self.client.scan_concurrency()) {
+            let group_blocks = self.client.get_block_windows(group)?;
+            for (batch, blocks) in group.iter().zip(group_blocks) {
+                for (height, commitment, record) in find_owned_records(blocks, view_key) {
+                    progress.records_found += 1;
+                    let event = ScanEvent::Record {
+                        height,
+                        commitment,
+                        record,
+                    };
+                    if callback(event).is_break() {
+                        return Ok(progress);
+                    }
+                }
+
+                self.store.save(&key, batch.end)?;
+                progress.scanned_height = batch.end;
+                if callback(ScanEvent::Progress(progress)).is_break() {
+                    return Ok(progress);
+                }
+            }
+        }
+
+        Ok(progress)
+    }
+
+    /// Scan the ledger like [`RecordScanner::scan_with`], sending every event to a channel. The
+    /// scan stops early if the receiving end of the channel is dropped.
+    pub fn scan_to_channel(
+        &self,
+        view_key: &ViewKey<N>,
+        end_height: Option<u32>,
+        sender: Sender<ScanEvent<N>>,
+    ) -> Result<ScanProgress> {
+        self.scan_with(view_key, end_height, |event| match sender.send(event) {
+            Ok(()) => ControlFlow::Continue(()),
+            Err(_) => ControlFlow::Break(()),
+        })
+    }
+
+    /// Scan the ledger like [`RecordScanner::scan_with`], collecting the records found
+    pub fn scan(
+        &self,
+        view_key: &ViewKey<N>,
+        end_height: Option<u32>,
+    ) -> Result<Vec<(Field<N>, Record<N, Ciphertext<N>>)>> {
+        let mut records = vec![];
+        self.scan_with(view_key, end_height, |event| {
+            if let ScanEvent::Record {
+                commitment, record, ..
+            } = event
+            {
+                records.push((commitment, record));
+            }
+            ControlFlow::Continue(())
+        })?;
+        Ok(records)
+    }
+
+    fn checkpoint_key(view_key: &ViewKey<N>) -> String {
+        view_key.to_address().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aleo_tools::test_utils::MockLedger;
+
+    #[test]
+    fn test_scans_resume_from_checkpoints() {
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_genesis_block().unwrap();
+        let view_key =
+            ViewKey::try_from(PrivateKey::<Testnet3>::new(&mut TestRng::default()).unwrap())
+                .unwrap();
+        let scanner = RecordScanner::new(ledger.client(), MemoryCheckpointStore::new());
+        assert_eq!(scanner.checkpoint(&view_key).unwrap(), None);
+
+        // Ensure progress is reported and the checkpoint advances to the latest block
+        let mut events = vec![];
+        let progress = scanner
+            .scan_with(&view_key, None, |event| {
+                events.push(event);
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+        assert!(progress.is_complete());
+        assert_eq!(progress.records_found, 0);
+        assert_eq!(progress.fraction(), 1.0);
+        assert!(
+            matches!(events.as_slice(), [ScanEvent::Progress(progress)] if progress.scanned_height == 1)
+        );
+        assert_eq!(scanner.checkpoint(&view_key).unwrap(), Some(1));
+
+        // Ensure a resumed scan does not fetch blocks which were already scanned
+        let progress = scanner
+            .scan_with(&view_key, None, |_| panic!("No events expected"))
+            .unwrap();
+        assert_eq!(progress.start_height, 1);
+        assert!(progress.is_complete());
+
+        // Ensure resetting the checkpoint starts the scan over
+        scanner.reset(&view_key).unwrap();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        scanner.scan_to_channel(&view_key, None, sender).unwrap();
+        assert_eq!(receiver.iter().count(), 1);
+    }
+}
@@ -0,0 +1,146 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, RwLock},
+};
+
+use snarkvm::prelude::Result;
+
+/// Storage for the heights up to which accounts have been scanned.
+///
+/// Checkpoints are keyed by the address of the scanned account rather than by its view key, so
+/// that no secret material ends up in the store.
+pub trait CheckpointStore: Debug + Send + Sync {
+    /// Get the height of the first block which has not been scanned yet for the given key
+    fn load(&self, key: &str) -> Result<Option<u32>>;
+
+    /// Record that all blocks below the given height have been scanned for the given key
+    fn save(&self, key: &str, height: u32) -> Result<()>;
+
+    /// Forget the checkpoint of the given key, so the next scan starts over
+    fn clear(&self, key: &str) -> Result<()>;
+}
+
+/// Checkpoint store kept in memory, for scans which do not need to survive a restart
+#[derive(Debug, Default)]
+pub struct MemoryCheckpointStore {
+    checkpoints: RwLock<HashMap<String, u32>>,
+}
+
+impl MemoryCheckpointStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CheckpointStore for MemoryCheckpointStore {
+    fn load(&self, key: &str) -> Result<Option<u32>> {
+        Ok(self.checkpoints.read().unwrap().get(key).copied())
+    }
+
+    fn save(&self, key: &str, height: u32) -> Result<()> {
+        self.checkpoints
+            .write()
+            .unwrap()
+            .insert(key.to_string(), height);
+        Ok(())
+    }
+
+    fn clear(&self, key: &str) -> Result<()> {
+        self.checkpoints.write().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+/// Checkpoint store persisting all checkpoints to a single JSON file.
+///
+/// The file is rewritten through a temporary file on every save, so an interrupted write leaves
+/// the previous checkpoints intact.
+#[derive(Debug)]
+pub struct FileCheckpointStore {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl FileCheckpointStore {
+    /// Create a store backed by the file at the given path. The file is created on the first save.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Get the path of the file backing the store
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read(&self) -> Result<HashMap<String, u32>> {
+        if !self.path.exists() {
+            return Ok(HashMap::new());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(&self.path)?)?)
+    }
+
+    fn write(&self, checkpoints: &HashMap<String, u32>) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temporary_path = self.path.with_extension("tmp");
+        fs::write(&temporary_path, serde_json::to_string(checkpoints)?)?;
+        fs::rename(temporary_path, &self.path)?;
+        Ok(())
+    }
+}
+
+impl CheckpointStore for FileCheckpointStore {
+    fn load(&self, key: &str) -> Result<Option<u32>> {
+        let _lock = self.lock.lock().unwrap();
+        Ok(self.read()?.get(key).copied())
+    }
+
+    fn save(&self, key: &str, height: u32) -> Result<()> {
+        let _lock = self.lock.lock().unwrap();
+        let mut checkpoints = self.read()?;
+        checkpoints.insert(key.to_string(), height);
+        self.write(&checkpoints)
+    }
+
+    fn clear(&self, key: &str) -> Result<()> {
+        let _lock = self.lock.lock().unwrap();
+        let mut checkpoints = self.read()?;
+        if checkpoints.remove(key).is_some() {
+            self.write(&checkpoints)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_checkpoints_are_persisted() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("scanner").join("checkpoints.json");
+
+        let store = FileCheckpointStore::new(&path);
+        assert_eq!(store.load("aleo1first").unwrap(), None);
+        store.save("aleo1first", 100).unwrap();
+        store.save("aleo1second", 50).unwrap();
+        store.save("aleo1first", 150).unwrap();
+
+        // Ensure checkpoints survive the store being recreated
+        let store = FileCheckpointStore::new(&path);
+        assert_eq!(store.load("aleo1first").unwrap(), Some(150));
+        assert_eq!(store.load("aleo1second").unwrap(), Some(50));
+
+        store.clear("aleo1first").unwrap();
+        assert_eq!(store.load("aleo1first").unwrap(), None);
+        assert_eq!(store.load("aleo1second").unwrap(), Some(50));
+    }
+}
@@ -1,3 +1,9 @@
+pub mod mock_ledger;
+pub use mock_ledger::*;
+
+pub mod mock_server;
+pub use mock_server::*;
+
 use snarkvm::file::Manifest;
 use snarkvm::prelude::*;
 
@@ -0,0 +1,290 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, RwLock},
+};
+
+use snarkvm::{circuit::prelude::IndexMap, ledger::block::*, prelude::*};
+
+use crate::aleo_tools::api::{
+    decode_path_segment, AleoAPIClient, Method, Transport, TransportRequest, TransportResponse,
+};
+
+/// Base url used by clients created from a [`MockLedger`]
+pub const MOCK_NODE_URL: &str = "http://mock.node";
+
+#[derive(Debug)]
+struct LedgerState<N: Network> {
+    blocks: BTreeMap<u32, Block<N>>,
+    programs: IndexMap<ProgramID<N>, Program<N>>,
+    mappings: HashMap<(ProgramID<N>, Identifier<N>), IndexMap<String, Value<N>>>,
+    transition_ids: HashMap<Field<N>, N::TransitionID>,
+    memory_pool: Vec<Transaction<N>>,
+    broadcasts: Vec<Transaction<N>>,
+    broadcast_response: Option<TransportResponse>,
+}
+
+/// In-memory stand-in for an Aleo node.
+///
+/// Tests seed the ledger with blocks, programs and mapping values, and then hand it to an
+/// [`AleoAPIClient`] as its [`Transport`] so the client (and anything built on top of it, like the
+/// `ProgramManager`) can be exercised without network access. Cloning the ledger shares its state.
+#[derive(Clone, Debug)]
+pub struct MockLedger<N: Network> {
+    network_id: String,
+    state: Arc<RwLock<LedgerState<N>>>,
+}
+
+impl<N: Network> MockLedger<N> {
+    pub fn new(network_id: &str) -> Self {
+        let state = LedgerState {
+            blocks: BTreeMap::new(),
+            programs: IndexMap::new(),
+            mappings: HashMap::new(),
+            transition_ids: HashMap::new(),
+            memory_pool: vec![],
+            broadcasts: vec![],
+            broadcast_response: None,
+        };
+        Self {
+            network_id: network_id.to_string(),
+            state: Arc::new(RwLock::new(state)),
+        }
+    }
+
+    /// Create an API client which is served by this ledger
+    pub fn client(&self) -> AleoAPIClient<N> {
+        AleoAPIClient::with_transport(MOCK_NODE_URL, &self.network_id, self.clone()).unwrap()
+    }
+
+    /// Add a block to the ledger, indexing the inputs and outputs of its transitions
+    pub fn add_block(&self, block: Block<N>) {
+        let mut state = self.state.write().unwrap();
+        for transition in block.transitions() {
+            let ids = transition
+                .inputs()
+                .iter()
+                .map(|input| *input.id())
+                .chain(transition.outputs().iter().map(|output| *output.id()));
+            for id in ids {
+                state.transition_ids.insert(id, *transition.id());
+            }
+        }
+        state.blocks.insert(block.height(), block);
+    }
+
+    /// Add the genesis block of the network to the ledger
+    pub fn add_genesis_block(&self) -> Result<()> {
+        self.add_block(Block::from_bytes_le(N::genesis_bytes())?);
+        Ok(())
+    }
+
+    /// Add a deployed program to the ledger
+    pub fn add_program(&self, program: Program<N>) {
+        let mut state = self.state.write().unwrap();
+        state.programs.insert(*program.id(), program);
+    }
+
+    /// Set the value stored under a key of a program mapping
+    pub fn set_mapping_value(
+        &self,
+        program_id: &str,
+        mapping_name: &str,
+        key: &str,
+        value: Value<N>,
+    ) -> Result<()> {
+        let program_id = ProgramID::from_str(program_id)?;
+        let mapping_name = Identifier::from_str(mapping_name)?;
+        let mut state = self.state.write().unwrap();
+        state
+            .mappings
+            .entry((program_id, mapping_name))
+            .or_default()
+            .insert(Self::normalize_key(key), value);
+        Ok(())
+    }
+
+    /// Mark an input or output ID (e.g. a record serial number) as consumed by a transition
+    pub fn add_transition_id(&self, input_or_output_id: Field<N>, transition_id: N::TransitionID) {
+        let mut state = self.state.write().unwrap();
+        state
+            .transition_ids
+            .insert(input_or_output_id, transition_id);
+    }
+
+    /// Add a transaction to the memory pool
+    pub fn add_to_memory_pool(&self, transaction: Transaction<N>) {
+        self.state.write().unwrap().memory_pool.push(transaction);
+    }
+
+    /// Respond to every subsequent broadcast with the given status and body instead of accepting it
+    pub fn set_broadcast_response(&self, status: u16, body: &str) {
+        self.state.write().unwrap().broadcast_response = Some(TransportResponse::new(status, body));
+    }
+
+    /// Get the transactions broadcast to the ledger so far
+    pub fn broadcast_transactions(&self) -> Vec<Transaction<N>> {
+        self.state.read().unwrap().broadcasts.clone()
+    }
+
+    /// Serve a request the way an Aleo node's REST API would
+    pub fn handle(&self, request: &TransportRequest) -> TransportResponse {
+        let prefix = format!("/{}/", self.network_id);
+        let Some((_, route)) = request.url.split_once(&prefix) else {
+            return TransportResponse::new(404, "Unknown network");
+        };
+        let (path, query) = route.split_once('?').unwrap_or((route, ""));
+        let segments = path.split('/').collect::<Vec<_>>();
+
+        let result = match (request.method, segments.as_slice()) {
+            (Method::Post, ["transaction", "broadcast"]) => {
+                return self.broadcast(request.body.as_deref().unwrap_or_default())
+            }
+            (Method::Get, _) => self.get(&segments, query),
+            _ => Ok(None),
+        };
+        match result {
+            Ok(Some(body)) => TransportResponse::new(200, body),
+            Ok(None) => TransportResponse::new(404, format!("Route '{path}' not found")),
+            // Nodes answer every error of the ledger with an internal server error
+            Err(error) => TransportResponse::new(500, format!("Something went wrong: {error}")),
+        }
+    }
+
+    fn get(&self, segments: &[&str], query: &str) -> Result<Option<String>> {
+        let state = self.state.read().unwrap();
+        let latest = || {
+            state
+                .blocks
+                .values()
+                .next_back()
+                .ok_or_else(|| anyhow!("The ledger has no blocks"))
+        };
+
+        let body = match segments {
+            ["latest", "height"] => serde_json::to_string(&latest()?.height())?,
+            ["latest", "hash"] => serde_json::to_string(&latest()?.hash())?,
+            ["latest", "block"] => serde_json::to_string(latest()?)?,
+            // The mock keeps no block tree, so the state root recorded by the latest block stands
+            // in for the current one
+            ["latest", "stateRoot"] => serde_json::to_string(&latest()?.previous_state_root())?,
+            ["block", height_or_hash] => {
+                let block = match height_or_hash.parse::<u32>() {
+                    Ok(height) => state.blocks.get(&height),
+                    Err(_) => state
+                        .blocks
+                        .values()
+                        .find(|block| block.hash().to_string() == *height_or_hash),
+                };
+                match block {
+                    Some(block) => serde_json::to_string(block)?,
+                    None => return Ok(None),
+                }
+            }
+            ["blocks"] => {
+                let mut start = 0u32;
+                let mut end = 0u32;
+                for pair in query.split('&') {
+                    match pair.split_once('=') {
+                        Some(("start", value)) => start = value.parse()?,
+                        Some(("end", value)) => end = value.parse()?,
+                        _ => (),
+                    }
+                }
+                let blocks = state.blocks.range(start..end).map(|(_, block)| block);
+                serde_json::to_string(&blocks.collect::<Vec<_>>())?
+            }
+            ["transaction", transaction_id] => {
+                let transaction = state
+                    .blocks
+                    .values()
+                    .flat_map(|block| block.transactions().iter())
+                    .map(|confirmed| confirmed.transaction())
+                    .chain(state.memory_pool.iter())
+                    .find(|transaction| transaction.id().to_string() == *transaction_id);
+                match transaction {
+                    Some(transaction) => serde_json::to_string(transaction)?,
+                    None => return Ok(None),
+                }
+            }
+            ["memoryPool", "transactions"] => serde_json::to_string(&state.memory_pool)?,
+            ["program", program_id] => {
+                match state.programs.get(&ProgramID::from_str(program_id)?) {
+                    Some(program) => serde_json::to_string(program)?,
+                    None => return Ok(None),
+                }
+            }
+            ["program", program_id, "mappings"] => {
+                match state.programs.get(&ProgramID::from_str(program_id)?) {
+                    Some(program) => {
+                        serde_json::to_string(&program.mappings().keys().collect::<Vec<_>>())?
+                    }
+                    None => return Ok(None),
+                }
+            }
+            ["program", program_id, "mapping", mapping_name, key] => {
+                let mapping = (
+                    ProgramID::from_str(program_id)?,
+                    Identifier::from_str(mapping_name)?,
+                );
+                let key = Self::normalize_key(&decode_path_segment(key)?);
+                let value = state
+                    .mappings
+                    .get(&mapping)
+                    .and_then(|values| values.get(&key));
+                // Nodes answer with `null` for keys which are not present in a mapping
+                serde_json::to_string(&value)?
+            }
+            ["find", "blockHash", transaction_id] => {
+                let block = state.blocks.values().find(|block| {
+                    block.transactions().iter().any(|confirmed| {
+                        confirmed.transaction().id().to_string() == *transaction_id
+                    })
+                });
+                match block {
+                    Some(block) => serde_json::to_string(&block.hash())?,
+                    None => return Ok(None),
+                }
+            }
+            ["find", "transitionID", input_or_output_id] => {
+                match state
+                    .transition_ids
+                    .get(&Field::from_str(input_or_output_id)?)
+                {
+                    Some(transition_id) => serde_json::to_string(transition_id)?,
+                    None => bail!("Failed to find the transition ID for the given input or output ID '{input_or_output_id}'"),
+                }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(body))
+    }
+
+    fn broadcast(&self, body: &str) -> TransportResponse {
+        let mut state = self.state.write().unwrap();
+        if let Some(response) = state.broadcast_response.clone() {
+            return response;
+        }
+        match serde_json::from_str::<Transaction<N>>(body) {
+            Ok(transaction) => {
+                let id = transaction.id();
+                state.broadcasts.push(transaction.clone());
+                state.memory_pool.push(transaction);
+                TransportResponse::new(200, serde_json::to_string(&id).unwrap_or_default())
+            }
+            Err(error) => TransportResponse::new(400, format!("Invalid transaction: {error}")),
+        }
+    }
+
+    // Keys are compared in their canonical plaintext form so `1u32` and ` 1u32` match
+    fn normalize_key(key: &str) -> String {
+        Plaintext::<N>::from_str(key)
+            .map(|key| key.to_string())
+            .unwrap_or_else(|_| key.to_string())
+    }
+}
+
+impl<N: Network> Transport for MockLedger<N> {
+    fn send(&self, request: &TransportRequest) -> Result<TransportResponse> {
+        Ok(self.handle(request))
+    }
+}
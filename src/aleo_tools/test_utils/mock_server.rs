@@ -0,0 +1,199 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::JoinHandle,
+};
+
+use snarkvm::prelude::*;
+
+use super::MockLedger;
+use crate::aleo_tools::api::{AleoAPIClient, Method, TransportRequest, TransportResponse};
+
+/// A small HTTP server exposing a [`MockLedger`] through the same REST routes as an Aleo node.
+///
+/// Unlike using the ledger as an in-process transport, the server is reachable over the loopback
+/// interface, so it can also serve clients which are not built on the
+/// [`Transport`](crate::aleo_tools::api::Transport) abstraction. The server stops when dropped.
+pub struct MockNodeServer<N: Network> {
+    ledger: MockLedger<N>,
+    address: SocketAddr,
+    shutdown: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl<N: Network> MockNodeServer<N> {
+    /// Start serving the ledger on a random local port
+    pub fn start(ledger: MockLedger<N>) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let ledger = ledger.clone();
+            let shutdown = shutdown.clone();
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if shutdown.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    let Ok(stream) = stream else { continue };
+                    let ledger = ledger.clone();
+                    std::thread::spawn(move || {
+                        if let Err(error) = Self::serve(&ledger, stream) {
+                            println!("Mock node failed to serve request: {error}");
+                        }
+                    });
+                }
+            })
+        };
+
+        Ok(Self {
+            ledger,
+            address,
+            shutdown,
+            handle: Some(handle),
+        })
+    }
+
+    /// Get the ledger backing the server
+    pub fn ledger(&self) -> &MockLedger<N> {
+        &self.ledger
+    }
+
+    /// Get the base url of the server
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// Create an API client which talks to the server over HTTP
+    pub fn client(&self, network_id: &str) -> AleoAPIClient<N> {
+        AleoAPIClient::new(&self.base_url(), network_id).unwrap()
+    }
+
+    // Handle a single HTTP/1.1 request. Connections are closed after every response.
+    fn serve(ledger: &MockLedger<N>, stream: TcpStream) -> Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let mut parts = request_line.split_whitespace();
+        let method = match parts.next() {
+            Some("GET") => Some(Method::Get),
+            Some("POST") => Some(Method::Post),
+            _ => None,
+        };
+        let path = parts.next().unwrap_or_default().to_string();
+
+        let mut content_length = 0usize;
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header)?;
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse()?;
+                }
+            }
+        }
+        let mut body = vec![0u8; content_length];
+        reader.read_exact(&mut body)?;
+
+        let response = match method {
+            Some(method) => ledger.handle(&TransportRequest {
+                method,
+                url: path,
+                body: (method == Method::Post).then(|| String::from_utf8_lossy(&body).into()),
+                timeout: None,
+            }),
+            None => TransportResponse::new(405, "Method not allowed"),
+        };
+        Self::respond(stream, response)
+    }
+
+    fn respond(mut stream: TcpStream, response: TransportResponse) -> Result<()> {
+        let reason = match response.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            _ => "Internal Server Error",
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            response.status,
+            response.body.len(),
+            response.body
+        )?;
+        stream.flush()?;
+        Ok(())
+    }
+}
+
+impl<N: Network> Drop for MockNodeServer<N> {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        // Wake up the listener so it observes the shutdown flag
+        let _ = TcpStream::connect(self.address);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aleo_tools::test_utils::{HELLO_PROGRAM, MULTIPLY_IMPORT_PROGRAM, MULTIPLY_PROGRAM};
+    use crate::models::constants::TESTNET_ADDRESS;
+
+    #[test]
+    fn test_mock_node_server_serves_ledger() {
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_program(Program::from_str(MULTIPLY_PROGRAM).unwrap());
+        ledger.add_program(Program::from_str(MULTIPLY_IMPORT_PROGRAM).unwrap());
+        ledger.add_program(Program::credits().unwrap());
+        ledger
+            .set_mapping_value(
+                "credits.aleo",
+                "account",
+                TESTNET_ADDRESS,
+                Value::from_str("5u64").unwrap(),
+            )
+            .unwrap();
+
+        let server = MockNodeServer::start(ledger).unwrap();
+        let client = server.client("testnet3");
+
+        // Ensure programs and their imports are served
+        let imports = client.get_program_imports("double_test.aleo").unwrap();
+        assert_eq!(imports.len(), 1);
+        assert!(client.get_program("hello.aleo").is_err());
+
+        // Ensure programs seeded after startup are served
+        server
+            .ledger()
+            .add_program(Program::from_str(HELLO_PROGRAM).unwrap());
+        assert!(client.get_program("hello.aleo").is_ok());
+
+        // Ensure mapping values are served
+        let value = client
+            .get_mapping_value("credits.aleo", "account", TESTNET_ADDRESS)
+            .unwrap();
+        assert_eq!(value, Value::from_str("5u64").unwrap());
+
+        // Ensure an empty ledger reports errors for block queries
+        assert!(client.latest_height().is_err());
+        assert!(client.get_blocks(0, 10).unwrap().is_empty());
+
+        // Ensure nothing has been broadcast
+        assert!(server.ledger().broadcast_transactions().is_empty());
+    }
+}
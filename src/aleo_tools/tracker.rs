@@ -0,0 +1,370 @@
+use std::{
+    sync::mpsc::Sender,
+    thread,
+    time::{Duration, Instant},
+};
+
+use snarkvm::{ledger::block::Block, prelude::*};
+
+use crate::aleo_tools::api::AleoAPIClient;
+use crate::models::encrypted_data::TransactionState;
+
+/// Interval between two polls of the network by default
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Time after which a transaction which has not reached a final state is given up on by default
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+/// Number of blocks before the latest block searched when tracking starts, so transactions
+/// included while they were being broadcast are not missed
+pub const LOOKBACK_BLOCKS: u32 = 10;
+
+/// The state of a tracked transaction, with the block it was included in once it is known
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionStatus<N: Network> {
+    pub state: TransactionState,
+    pub block_hash: Option<N::BlockHash>,
+    pub block_height: Option<u32>,
+}
+
+impl<N: Network> TransactionStatus<N> {
+    fn new(state: TransactionState) -> Self {
+        Self {
+            state,
+            block_hash: None,
+            block_height: None,
+        }
+    }
+
+    fn in_block(state: TransactionState, block: &Block<N>) -> Self {
+        Self {
+            state,
+            block_hash: Some(block.hash()),
+            block_height: Some(block.height()),
+        }
+    }
+}
+
+/// Tracker following a broadcast transaction until it is confirmed, rejected or aborted.
+///
+/// The tracker polls the node of the API client, looking the transaction up by ID, then in the
+/// blocks produced since tracking started, then in the mempool:
+/// - `Processing` while the transaction has not been seen by the node
+/// - `Pending` while it waits in the mempool
+/// - `Confirmed` once it is accepted in a block
+/// - `Rejected` once it is included in a block but only its fee is spent
+/// - `Aborted` once a block aborts it
+/// - `Failed` if it does not reach one of the final states above before the timeout
+#[derive(Clone, Debug)]
+pub struct TransactionTracker<N: Network> {
+    client: AleoAPIClient<N>,
+    poll_interval: Duration,
+    timeout: Duration,
+}
+
+impl<N: Network> TransactionTracker<N> {
+    pub fn new(client: AleoAPIClient<N>) -> Self {
+        Self {
+            client,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Set the interval between two polls of the network
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Set the time after which a transaction which has not reached a final state is considered
+    /// failed
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Check the state of a transaction once, searching the blocks from the given height for
+    /// transactions which cannot be found by ID, e.g. aborted transactions
+    pub fn check(
+        &self,
+        transaction_id: N::TransactionID,
+        start_height: u32,
+    ) -> Result<TransactionStatus<N>> {
+        let mut next_height = start_height;
+        self.poll(
+            transaction_id,
+            &mut next_height,
+            TransactionState::Processing,
+        )
+    }
+
+    /// Poll the network until the transaction reaches a final state or the timeout expires.
+    /// Every change of state is reported to the callback, and the last status is returned. Polls
+    /// which fail, e.g. while the node is unreachable, leave the state unchanged and are retried
+    /// after the poll interval.
+    pub fn track(
+        &self,
+        transaction_id: N::TransactionID,
+        mut callback: impl FnMut(&TransactionStatus<N>),
+    ) -> Result<TransactionStatus<N>> {
+        let started = Instant::now();
+        let mut next_height = None;
+        let mut status = TransactionStatus::new(TransactionState::Processing);
+        callback(&status);
+
+        loop {
+            // The blocks are searched from shortly before the latest block when tracking starts
+            let polled = match next_height {
+                Some(ref mut next_height) => {
+                    self.poll(transaction_id, next_height, status.state.clone())
+                }
+                None => self.client.latest_height().and_then(|latest_height| {
+                    let start_height = latest_height.saturating_sub(LOOKBACK_BLOCKS);
+                    let next_height = next_height.insert(start_height);
+                    self.poll(transaction_id, next_height, status.state.clone())
+                }),
+            };
+            match polled {
+                Ok(polled) if polled != status => {
+                    status = polled;
+                    callback(&status);
+                }
+                Ok(_) => (),
+                Err(error) => {
+                    tracing::debug!("Failed to poll transaction {transaction_id}: {error}")
+                }
+            }
+            if status.state.is_final() {
+                return Ok(status);
+            }
+
+            if started.elapsed() >= self.timeout {
+                println!("❌ Transaction {transaction_id} did not complete before the timeout");
+                status = TransactionStatus::new(TransactionState::Failed);
+                callback(&status);
+                return Ok(status);
+            }
+            thread::sleep(self.poll_interval);
+        }
+    }
+
+    /// Track a transaction like [`TransactionTracker::track`], sending every change of state to a
+    /// channel
+    pub fn track_to_channel(
+        &self,
+        transaction_id: N::TransactionID,
+        sender: Sender<TransactionStatus<N>>,
+    ) -> Result<TransactionStatus<N>> {
+        self.track(transaction_id, |status| {
+            let _ = sender.send(status.clone());
+        })
+    }
+
+    /// Track a transaction like [`TransactionTracker::track`] on a background thread
+    pub fn spawn(
+        self,
+        transaction_id: N::TransactionID,
+        callback: impl FnMut(&TransactionStatus<N>) + Send + 'static,
+    ) -> thread::JoinHandle<Result<TransactionStatus<N>>> {
+        thread::spawn(move || self.track(transaction_id, callback))
+    }
+
+    // Poll the network once for the state of the transaction, advancing the height of the next
+    // block to search. Transactions which are neither in a block nor in the mempool keep their
+    // previous state, since they may be between the two.
+    fn poll(
+        &self,
+        transaction_id: N::TransactionID,
+        next_height: &mut u32,
+        previous: TransactionState,
+    ) -> Result<TransactionStatus<N>> {
+        // Accepted transactions and the fee transactions of rejected transactions are indexed
+        if let Ok(hash) = self.client.find_block_hash(transaction_id) {
+            let block = self.client.get_block_by_hash(hash)?;
+            let state =
+                Self::find_in_block(&block, transaction_id).unwrap_or(TransactionState::Confirmed);
+            return Ok(TransactionStatus::in_block(state, &block));
+        }
+
+        // Rejected and aborted transactions are only found by searching blocks
+        let latest_height = self.client.latest_height()?;
+        let windows = (*next_height..latest_height + 1)
+            .step_by(50)
+            .map(|start| start..start.saturating_add(50).min(latest_height + 1))
+            .collect::<Vec<_>>();
+        for blocks in self.client.get_block_windows(&windows)? {
+            for block in blocks {
+                if let Some(state) = Self::find_in_block(&block, transaction_id) {
+                    return Ok(TransactionStatus::in_block(state, &block));
+                }
+            }
+        }
+        *next_height = (*next_height).max(latest_height + 1);
+
+        let in_memory_pool = self
+            .client
+            .get_memory_pool_transactions()?
+            .iter()
+            .any(|transaction| transaction.id() == transaction_id);
+        match in_memory_pool {
+            true => Ok(TransactionStatus::new(TransactionState::Pending)),
+            false => Ok(TransactionStatus::new(previous)),
+        }
+    }
+
+    // Find the state of a transaction in a block, if the block includes or aborts it
+    fn find_in_block(
+        block: &Block<N>,
+        transaction_id: N::TransactionID,
+    ) -> Option<TransactionState> {
+        if block.aborted_transaction_ids().contains(&transaction_id) {
+            return Some(TransactionState::Aborted);
+        }
+        let confirmed = block.transactions().iter().find(|confirmed| {
+            confirmed.id() == transaction_id
+                || confirmed
+                    .to_unconfirmed_transaction_id()
+                    .is_ok_and(|unconfirmed_id| unconfirmed_id == transaction_id)
+        })?;
+        match confirmed.is_accepted() {
+            true => Some(TransactionState::Confirmed),
+            false => Some(TransactionState::Rejected),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aleo_tools::api::{RetryPolicy, Transport, TransportRequest, TransportResponse};
+    use crate::aleo_tools::test_utils::{MockLedger, MOCK_NODE_URL};
+
+    use snarkvm::ledger::block::Transaction;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Transport hiding the transactions of blocks, as if they had not been produced yet
+    #[derive(Debug)]
+    struct UnconfirmedTransport {
+        ledger: MockLedger<Testnet3>,
+    }
+
+    impl Transport for UnconfirmedTransport {
+        fn send(&self, request: &TransportRequest) -> Result<TransportResponse> {
+            if request.url.contains("/find/blockHash/") {
+                return Ok(TransportResponse::new(404, "Not found"));
+            }
+            if request.url.contains("/blocks?") {
+                return Ok(TransportResponse::new(200, "[]"));
+            }
+            self.ledger.send(request)
+        }
+    }
+
+    /// Transport answering the first requests with a service unavailable error
+    #[derive(Debug)]
+    struct FlakyTransport {
+        ledger: MockLedger<Testnet3>,
+        failures: AtomicU32,
+    }
+
+    impl Transport for FlakyTransport {
+        fn send(&self, request: &TransportRequest) -> Result<TransportResponse> {
+            let remaining_failures = self.failures.load(Ordering::SeqCst);
+            if remaining_failures > 0 {
+                self.failures
+                    .store(remaining_failures - 1, Ordering::SeqCst);
+                return Ok(TransportResponse::new(503, "Service unavailable"));
+            }
+            self.ledger.send(request)
+        }
+    }
+
+    fn genesis_transaction() -> Transaction<Testnet3> {
+        let genesis = Block::<Testnet3>::from_bytes_le(Testnet3::genesis_bytes()).unwrap();
+        let confirmed = genesis.transactions().iter().next().unwrap();
+        confirmed.transaction().clone()
+    }
+
+    #[test]
+    fn test_confirmed_transactions_are_tracked() {
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_genesis_block().unwrap();
+        let tracker = TransactionTracker::new(ledger.client());
+
+        let mut states = vec![];
+        let status = tracker
+            .track(genesis_transaction().id(), |status| {
+                states.push(status.state.clone())
+            })
+            .unwrap();
+        assert_eq!(status.state, TransactionState::Confirmed);
+        assert_eq!(status.block_height, Some(0));
+        assert_eq!(
+            states,
+            vec![TransactionState::Processing, TransactionState::Confirmed]
+        );
+    }
+
+    #[test]
+    fn test_failed_polls_do_not_stop_tracking() {
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_genesis_block().unwrap();
+        let client = AleoAPIClient::<Testnet3>::builder()
+            .base_url(MOCK_NODE_URL)
+            .transport(FlakyTransport {
+                ledger,
+                failures: AtomicU32::new(3),
+            })
+            .retry_policy(RetryPolicy::no_retry())
+            .build()
+            .unwrap();
+        let tracker = TransactionTracker::new(client).with_poll_interval(Duration::from_millis(10));
+
+        let mut states = vec![];
+        let status = tracker
+            .track(genesis_transaction().id(), |status| {
+                states.push(status.state.clone())
+            })
+            .unwrap();
+        assert_eq!(status.state, TransactionState::Confirmed);
+        assert_eq!(
+            states,
+            vec![TransactionState::Processing, TransactionState::Confirmed]
+        );
+    }
+
+    #[test]
+    fn test_pending_transactions_time_out() {
+        let ledger = MockLedger::<Testnet3>::new("testnet3");
+        ledger.add_genesis_block().unwrap();
+        let transaction = genesis_transaction();
+        ledger.add_to_memory_pool(transaction.clone());
+        let client = AleoAPIClient::<Testnet3>::builder()
+            .base_url(MOCK_NODE_URL)
+            .transport(UnconfirmedTransport { ledger })
+            .build()
+            .unwrap();
+        let tracker = TransactionTracker::new(client)
+            .with_poll_interval(Duration::from_millis(10))
+            .with_timeout(Duration::from_millis(50));
+
+        let status = tracker.check(transaction.id(), 0).unwrap();
+        assert_eq!(status.state, TransactionState::Pending);
+        assert_eq!(status.block_hash, None);
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let status = tracker.track_to_channel(transaction.id(), sender).unwrap();
+        assert_eq!(status.state, TransactionState::Failed);
+        let states = receiver
+            .iter()
+            .map(|status| status.state)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            states,
+            vec![
+                TransactionState::Processing,
+                TransactionState::Pending,
+                TransactionState::Failed
+            ]
+        );
+    }
+}
@@ -355,6 +355,14 @@ impl TransactionState {
         }
     }
 
+    /// Whether the transaction can no longer change state
+    pub fn is_final(&self) -> bool {
+        !matches!(
+            self,
+            TransactionState::Processing | TransactionState::Pending
+        )
+    }
+
     pub fn to_str(&self) -> String {
         match self {
             TransactionState::Processing => "Processing".to_string(),